@@ -13,13 +13,14 @@ use crate::opt;
 pub fn create_cli() -> App<'static, 'static> {
     App::new("jrnlvw - journal viewer")
         .version(crate_version!())
-        .author(crate_authors!("\n"))
+        .author(env!("CARGO_PKG_AUTHORS"))
         .about(crate_description!())
         .setting(AppSettings::ColorAlways)
         .arg(
             Arg::with_name(opt::LOGFILE)
-                .help("The journal json logfile to view")
-                .required(true),
+                .help("The journal json logfile(s) to view")
+                .required(true)
+                .multiple(true),
         )
         .arg(
             Arg::with_name(opt::LIST_BOOTS_FLAG)
@@ -29,6 +30,114 @@ pub fn create_cli() -> App<'static, 'static> {
                 .short("l")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name(opt::INTERACTIVE_FLAG)
+                .help("Browse log entries in an interactive terminal viewer, with filters from the command line as initial filters")
+                .required(false)
+                .long(opt::INTERACTIVE_FLAG)
+                .short("i")
+                .takes_value(false)
+                .conflicts_with_all(&[
+                    opt::OUTPUT_FILE,
+                    opt::LIST_BOOTS_FLAG,
+                    opt::LIST_HOSTS_FLAG,
+                    opt::STATS_FLAG,
+                    opt::HISTOGRAM_FLAG,
+                    opt::DIGEST_FLAG,
+                    opt::DIFF_FLAG,
+                    opt::UNITS_REPORT_FLAG,
+                ]),
+        )
+        .arg(
+            Arg::with_name(opt::LIST_HOSTS_FLAG)
+                .help("List all hosts, and their boots, from provided logfile")
+                .required(false)
+                .long(opt::LIST_HOSTS_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::STATS_FLAG)
+                .help("Print statistics of matching log entries per boot and overall: entries per unit, priority, transport and hour, most frequent identifiers and time range")
+                .required(false)
+                .long(opt::STATS_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::TOP_COUNT)
+                .help("Number of most frequent identifiers listed in statistics (default 10)")
+                .required(false)
+                .long(opt::TOP_COUNT)
+                .takes_value(true)
+                .requires(opt::STATS_FLAG),
+        )
+        .arg(
+            Arg::with_name(opt::HISTOGRAM_FLAG)
+                .help("Print a bar chart of the number of matching log entries over time, e.g. to find bursts of errors or gaps in logging")
+                .required(false)
+                .long(opt::HISTOGRAM_FLAG)
+                .takes_value(false)
+                .conflicts_with(opt::STATS_FLAG),
+        )
+        .arg(
+            Arg::with_name(opt::HISTOGRAM_INTERVAL)
+                .help("Histogram bucket length, with unit ms, s, m, h or d, e.g. 5m. Chosen from the time range of entries if not provided")
+                .required(false)
+                .long(opt::HISTOGRAM_INTERVAL)
+                .takes_value(true)
+                .requires(opt::HISTOGRAM_FLAG),
+        )
+        .arg(
+            Arg::with_name(opt::STACKED_FLAG)
+                .help("Stack histogram bars by priority: err and above, warning, notice and info")
+                .required(false)
+                .long(opt::STACKED_FLAG)
+                .takes_value(false)
+                .requires(opt::HISTOGRAM_FLAG),
+        )
+        .arg(
+            Arg::with_name(opt::ASCII_FLAG)
+                .help("Draw histogram bars with ascii characters instead of unicode blocks")
+                .required(false)
+                .long(opt::ASCII_FLAG)
+                .takes_value(false)
+                .requires(opt::HISTOGRAM_FLAG),
+        )
+        .arg(
+            Arg::with_name(opt::DIGEST_FLAG)
+                .help("Group matching messages into templates, with numbers, hex values, UUIDs, paths and IP addresses masked, and list each template with count, time range, units and an example. Most severe and frequent templates first")
+                .required(false)
+                .long(opt::DIGEST_FLAG)
+                .takes_value(false)
+                .conflicts_with_all(&[opt::STATS_FLAG, opt::HISTOGRAM_FLAG]),
+        )
+        .arg(
+            Arg::with_name(opt::DIFF_FLAG)
+                .help("Compare matching messages of two boots (--boot <A> --boot <B>), two logfiles, or the last two boots in logfile, by message template and unit: templates only in one side, large count changes, new errors in B and units started or failed in only one side")
+                .required(false)
+                .long(opt::DIFF_FLAG)
+                .takes_value(false)
+                .conflicts_with_all(&[opt::STATS_FLAG, opt::HISTOGRAM_FLAG, opt::DIGEST_FLAG]),
+        )
+        .arg(
+            Arg::with_name(opt::UNITS_REPORT_FLAG)
+                .help("Print start, stop, failure and restart timelines of units per boot, from systemd messages among matching entries, with restart loops and units that never started flagged")
+                .required(false)
+                .long(opt::UNITS_REPORT_FLAG)
+                .takes_value(false)
+                .conflicts_with_all(&[
+                    opt::STATS_FLAG,
+                    opt::HISTOGRAM_FLAG,
+                    opt::DIGEST_FLAG,
+                    opt::DIFF_FLAG,
+                ]),
+        )
+        .arg(
+            Arg::with_name(opt::GROUP_BY_HOST_FLAG)
+                .help("Group log entries by host, then by boot")
+                .required(false)
+                .long(opt::GROUP_BY_HOST_FLAG)
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name(opt::KERNEL_FLAG)
                 .help("Only print log entry originating from the kernel")
@@ -39,7 +148,7 @@ pub fn create_cli() -> App<'static, 'static> {
         )
         .arg(
             Arg::with_name(opt::LOG_LEVEL)
-                .help("Set entry log level to print, default 7 = debug")
+                .help("Set entry log level(s) to print, as level (0-7) or name (emerg..debug), or range FROM..TO, e.g. err..warning. Default debug")
                 .required(false)
                 .long(opt::LOG_LEVEL)
                 .short("p")
@@ -54,6 +163,62 @@ pub fn create_cli() -> App<'static, 'static> {
                 .takes_value(true)
                 .multiple(true),
         )
+        .arg(
+            Arg::with_name(opt::GREP_FILTER)
+                .help("Only print log entries with a message matching pattern (regular expression), case insensitive if the pattern is all lower case")
+                .required(false)
+                .long(opt::GREP_FILTER)
+                .short("g")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(opt::FACILITY_FILTER)
+                .help("Syslog facility(s) to print, by number or name (kern, auth, daemon, local0 etc.)")
+                .required(false)
+                .long(opt::FACILITY_FILTER)
+                .takes_value(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name(opt::TRANSPORT_FILTER)
+                .help("Journal transport(s) to print: audit, driver, syslog, journal, stdout or kernel")
+                .required(false)
+                .long(opt::TRANSPORT_FILTER)
+                .takes_value(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name(opt::PID_FILTER)
+                .help("Process ID(s) to print")
+                .required(false)
+                .long(opt::PID_FILTER)
+                .takes_value(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name(opt::UID_FILTER)
+                .help("User ID(s) to print, as UID or range FROM..TO, e.g. 1000..")
+                .required(false)
+                .long(opt::UID_FILTER)
+                .takes_value(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name(opt::HOST_FILTER)
+                .help("Host name(s) to print")
+                .required(false)
+                .long(opt::HOST_FILTER)
+                .takes_value(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name(opt::MACHINE_FILTER)
+                .help("Machine ID(s) to print")
+                .required(false)
+                .long(opt::MACHINE_FILTER)
+                .takes_value(true)
+                .multiple(true),
+        )
         .arg(
             Arg::with_name(opt::NUM_OF_ENTRIES)
                 .help("Max amount of log entries to print for each boot (<NUMBER> > 0)")
@@ -62,6 +227,127 @@ pub fn create_cli() -> App<'static, 'static> {
                 .short("n")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(opt::NUM_OF_LINES)
+                .help("Max amount of log entries to print from the end of each boot (<NUMBER> > 0)")
+                .required(false)
+                .long(opt::NUM_OF_LINES)
+                .short("e")
+                .takes_value(true)
+                .conflicts_with(opt::NUM_OF_ENTRIES),
+        )
+        .arg(
+            Arg::with_name(opt::LIMIT_OVERALL_FLAG)
+                .help("Apply --number/--lines to all boots together instead of each boot")
+                .required(false)
+                .long(opt::LIMIT_OVERALL_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::OUTPUT_MODE)
+                .help("Output format: table (default), json (one journal entry per line, as journalctl -o json), csv, tsv or one of the journalctl text formats short, short-iso, short-precise, short-monotonic, with-unit, cat and verbose. The reports of --stats, --histogram, --digest, --diff and --units-report are printed as json with -o json")
+                .required(false)
+                .long(opt::OUTPUT_MODE)
+                .short("o")
+                .takes_value(true)
+                .possible_values(&[
+                    "table",
+                    "json",
+                    "csv",
+                    "tsv",
+                    "short",
+                    "short-iso",
+                    "short-precise",
+                    "short-monotonic",
+                    "with-unit",
+                    "cat",
+                    "verbose",
+                ]),
+        )
+        .arg(
+            Arg::with_name(opt::COLUMNS)
+                .help("Comma separated columns for csv/tsv output: journal fields (e.g. MESSAGE, _PID) or time, seqnum, boot, priority, unit. Default time,boot,seqnum,priority,unit,MESSAGE")
+                .required(false)
+                .long(opt::COLUMNS)
+                .takes_value(true)
+                .use_delimiter(true)
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name(opt::DETAILS_FLAG)
+                .help("Print all fields of each log entry below the entry in table output, for all fields in other output formats use -o verbose")
+                .required(false)
+                .long(opt::DETAILS_FLAG)
+                .short("x")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::TRUNCATE_FLAG)
+                .help("Cut long message lines at terminal width in table output")
+                .required(false)
+                .long(opt::TRUNCATE_FLAG)
+                .takes_value(false)
+                .conflicts_with(opt::WRAP_FLAG),
+        )
+        .arg(
+            Arg::with_name(opt::WRAP_FLAG)
+                .help("Wrap long message lines at terminal width in table output, wrapped lines are indented to the message column")
+                .required(false)
+                .long(opt::WRAP_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::HIDE_COLUMNS)
+                .help("Columns to hide in table output, comma separated")
+                .required(false)
+                .long(opt::HIDE_COLUMNS)
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&opt::HIDEABLE_COLUMNS),
+        )
+        .arg(
+            Arg::with_name(opt::COLOR_MODE)
+                .help("Colorize output by priority, auto enables colors if output is a terminal and NO_COLOR is not set")
+                .required(false)
+                .long(opt::COLOR_MODE)
+                .takes_value(true)
+                .possible_values(&["always", "never", "auto"]),
+        )
+        .arg(
+            Arg::with_name(opt::FORMAT_TEMPLATE)
+                .help("Print log entries using a format template, e.g. \"{time:%H:%M:%S%.3f} {priority:name} {unit:<20} {MESSAGE}\". Fields are journal fields or time, seqnum, boot, priority, unit. Format specifiers: strftime format for time, name/num for priority, [<>^][width][.max] alignment for all others")
+                .required(false)
+                .long(opt::FORMAT_TEMPLATE)
+                .short("F")
+                .takes_value(true)
+                .conflicts_with_all(&[opt::OUTPUT_MODE, opt::COLUMNS]),
+        )
+        .arg(
+            Arg::with_name(opt::REVERSE_FLAG)
+                .help("Print newest log entries (and boots) first, entry limits still select from the oldest (--number) or newest (--lines) entries")
+                .required(false)
+                .long(opt::REVERSE_FLAG)
+                .short("r")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::SORT_KEY)
+                .help("Sort log entries within each boot, default is file order")
+                .required(false)
+                .long(opt::SORT_KEY)
+                .short("s")
+                .takes_value(true)
+                .possible_values(&["time", "seqnum", "priority"]),
+        )
+        .arg(
+            Arg::with_name(opt::FLAT_FLAG)
+                .help("Print log entries from all boots and logfiles as a single timeline, sorted by time unless --sort is given")
+                .required(false)
+                .long(opt::FLAT_FLAG)
+                .short("f")
+                .takes_value(false)
+                .conflicts_with(opt::GROUP_BY_HOST_FLAG),
+        )
         .arg(
             Arg::with_name(opt::BOOT_FILTER)
                 .help("Specify a boot(s) to show ")
@@ -104,7 +390,73 @@ pub fn create_cli() -> App<'static, 'static> {
             .short("D")
             .takes_value(true)
         )
-    //Todo, add arg for creating a output file
+        .arg(
+            Arg::with_name(opt::TIMESTAMP_MODE)
+                .help("Timestamp to display: wall clock time, time since boot or time since previous entry of the boot in the file")
+                .required(false)
+                .long(opt::TIMESTAMP_MODE)
+                .takes_value(true)
+                .possible_values(&["realtime", "monotonic", "delta"]),
+        )
+        .arg(
+            Arg::with_name(opt::PRECISE_FLAG)
+                .help("Display wall clock timestamps with microsecond precision")
+                .required(false)
+                .long(opt::PRECISE_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::BOOT_TIME)
+                .help("Only display log entries within a time range since boot, e.g. 0s..30s, 500ms.. or ..2m")
+                .required(false)
+                .long(opt::BOOT_TIME)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(opt::CURSOR)
+                .help("Start displaying log entries at the entry with the specified cursor")
+                .required(false)
+                .long(opt::CURSOR)
+                .short("c")
+                .takes_value(true)
+                .conflicts_with_all(&[opt::AFTER_CURSOR, opt::CURSOR_FILE]),
+        )
+        .arg(
+            Arg::with_name(opt::AFTER_CURSOR)
+                .help("Start displaying log entries after the entry with the specified cursor")
+                .required(false)
+                .long(opt::AFTER_CURSOR)
+                .takes_value(true)
+                .conflicts_with(opt::CURSOR_FILE),
+        )
+        .arg(
+            Arg::with_name(opt::SHOW_CURSOR_FLAG)
+                .help("Print the cursor of the last displayed log entry")
+                .required(false)
+                .long(opt::SHOW_CURSOR_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::CURSOR_FILE)
+                .help("Start displaying log entries after the cursor stored in file (if it exists), and store the cursor of the last displayed entry in file")
+                .required(false)
+                .long(opt::CURSOR_FILE)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(opt::NO_PAGER_FLAG)
+                .help("Do not pipe output into a pager, by default $PAGER or 'less -FRSX' is used when output is a terminal")
+                .required(false)
+                .long(opt::NO_PAGER_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(opt::OUTPUT_FILE)
+                .help("Write output to file instead of stdout, the file is replaced when all output is written. Output is gzip compressed if the file name ends with .gz")
+                .required(false)
+                .long(opt::OUTPUT_FILE)
+                .takes_value(true),
+        )
 
     // Todo: more filters
 }
@@ -0,0 +1,64 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: color.rs
+// File Description: Terminal colors (ANSI escape sequences) for log output
+// License: MIT
+//------------------------------------------------------------------------------
+
+//------------------------------------------------------------------------------
+// ANSI escape sequences
+//------------------------------------------------------------------------------
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+
+//------------------------------------------------------------------------------
+// Colors type
+//------------------------------------------------------------------------------
+
+// Colorizes output text, all functions return the text unchanged if colors are disabled
+#[derive(Debug, Clone, Copy)]
+pub struct Colors {
+    enabled: bool,
+}
+
+impl Colors {
+    pub fn new(enabled: bool) -> Colors {
+        return Colors { enabled };
+    }
+
+    // Color text by priority: red for err and above, yellow for warning, bold for notice
+    // and dim for debug. Info and entries without priority are not colored.
+    pub fn priority(&self, text: &str, priority: Option<u32>) -> String {
+        let style = match priority {
+            Some(0..=3) => RED,
+            Some(4) => YELLOW,
+            Some(5) => BOLD,
+            Some(7) => DIM,
+            _ => return text.to_string(),
+        };
+        return self.paint(text, style);
+    }
+
+    // Color of boot and host headers
+    pub fn header(&self, text: &str) -> String {
+        return self.paint(text, BOLD_BLUE);
+    }
+
+    // Color of unit names
+    pub fn unit(&self, text: &str) -> String {
+        return self.paint(text, CYAN);
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if !self.enabled || text.is_empty() {
+            return text.to_string();
+        }
+        return format!("{}{}{}", style, text, RESET);
+    }
+}
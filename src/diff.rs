@@ -0,0 +1,293 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: diff.rs
+// File Description: Comparison of log entries of two boots or logfiles
+// License: MIT
+//------------------------------------------------------------------------------
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::color;
+use crate::digest::Normalizer;
+use crate::lifecycle::{self, UnitEvent};
+use crate::opt;
+use crate::parser::{FormattedLogEntry, LogEntry};
+
+//------------------------------------------------------------------------------
+// Diff constants
+//------------------------------------------------------------------------------
+
+// Count changes of templates present on both sides are reported if one count is at
+// least CHANGE_FACTOR times the other, and the counts differ by at least CHANGE_MIN
+const CHANGE_FACTOR: usize = 2;
+const CHANGE_MIN: usize = 5;
+// Most severe priority of error-level messages
+const ERROR_PRIORITY: u32 = 3;
+
+//------------------------------------------------------------------------------
+// Diff side type
+//------------------------------------------------------------------------------
+
+// Messages of one template and unit
+struct TemplateCount {
+    count: usize,
+    priority: Option<u32>,
+    example: String,
+}
+
+// Message templates and unit events of one side, i.e. a boot or a logfile
+pub struct DiffSide {
+    name: String,
+    entries: usize,
+    templates: HashMap<(String, String), TemplateCount>,
+    started: BTreeSet<String>,
+    failed: BTreeSet<String>,
+}
+
+impl DiffSide {
+    pub fn new(name: String) -> DiffSide {
+        return DiffSide {
+            name,
+            entries: 0,
+            templates: HashMap::new(),
+            started: BTreeSet::new(),
+            failed: BTreeSet::new(),
+        };
+    }
+
+    // Add entry to the template of its message and unit
+    pub fn add(
+        &mut self,
+        normalizer: &Normalizer,
+        log_entry: &LogEntry,
+        entry: &FormattedLogEntry,
+    ) {
+        self.entries += 1;
+        let key = (normalizer.template(&entry.message), entry.unit.clone());
+        let template = self.templates.entry(key).or_insert_with(|| TemplateCount {
+            count: 0,
+            priority: entry.priority,
+            example: entry.message.clone(),
+        });
+        template.count += 1;
+        template.priority = match (template.priority, entry.priority) {
+            (Some(p), Some(e)) => Some(p.min(e)),
+            (p, e) => p.or(e),
+        };
+
+        match lifecycle::unit_event(log_entry) {
+            Some((unit, UnitEvent::Started)) => {
+                self.started.insert(unit);
+            }
+            Some((unit, UnitEvent::Failed)) | Some((unit, UnitEvent::StartFailed)) => {
+                self.failed.insert(unit);
+            }
+            _ => {}
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Diff report types
+//------------------------------------------------------------------------------
+
+// Template and unit with message counts of both sides
+#[derive(Debug, Serialize)]
+pub struct TemplateDiff {
+    pub template: String,
+    pub unit: String,
+    pub priority: Option<u32>,
+    pub count_a: usize,
+    pub count_b: usize,
+    pub example: String,
+}
+
+// Differences between side A and side B
+#[derive(Debug, Serialize)]
+pub struct DiffReport {
+    pub a: String,
+    pub b: String,
+    pub entries_a: usize,
+    pub entries_b: usize,
+    // Error-level templates only in B
+    pub new_errors: Vec<TemplateDiff>,
+    pub only_a: Vec<TemplateDiff>,
+    // Templates only in B, except new errors
+    pub only_b: Vec<TemplateDiff>,
+    pub changed: Vec<TemplateDiff>,
+    pub started_only_a: Vec<String>,
+    pub started_only_b: Vec<String>,
+    pub failed_only_a: Vec<String>,
+    pub failed_only_b: Vec<String>,
+}
+
+impl DiffReport {
+    // Compare templates and unit events of both sides
+    pub fn new(a: DiffSide, b: DiffSide) -> DiffReport {
+        let mut report = DiffReport {
+            a: a.name.clone(),
+            b: b.name.clone(),
+            entries_a: a.entries,
+            entries_b: b.entries,
+            new_errors: Vec::new(),
+            only_a: Vec::new(),
+            only_b: Vec::new(),
+            changed: Vec::new(),
+            started_only_a: a.started.difference(&b.started).cloned().collect(),
+            started_only_b: b.started.difference(&a.started).cloned().collect(),
+            failed_only_a: a.failed.difference(&b.failed).cloned().collect(),
+            failed_only_b: b.failed.difference(&a.failed).cloned().collect(),
+        };
+
+        for (key, template) in &a.templates {
+            match b.templates.get(key) {
+                None => report.only_a.push(template_diff(key, Some(template), None)),
+                Some(other) => {
+                    let (min, max) = if template.count < other.count {
+                        (template.count, other.count)
+                    } else {
+                        (other.count, template.count)
+                    };
+                    if max >= min * CHANGE_FACTOR && max - min >= CHANGE_MIN {
+                        report
+                            .changed
+                            .push(template_diff(key, Some(template), Some(other)));
+                    }
+                }
+            }
+        }
+        for (key, template) in &b.templates {
+            if a.templates.contains_key(key) {
+                continue;
+            }
+            let diff = template_diff(key, None, Some(template));
+            if diff.priority.is_some_and(|p| p <= ERROR_PRIORITY) {
+                report.new_errors.push(diff);
+            } else {
+                report.only_b.push(diff);
+            }
+        }
+
+        // Most severe and frequent first, largest changes first. Ties are ordered by
+        // template and unit, templates are collected in hash map order
+        sort_by_severity(&mut report.new_errors);
+        sort_by_severity(&mut report.only_a);
+        sort_by_severity(&mut report.only_b);
+        report.changed.sort_by(|x, y| {
+            let x_change = x.count_a.abs_diff(x.count_b);
+            let y_change = y.count_a.abs_diff(y.count_b);
+            return y_change
+                .cmp(&x_change)
+                .then(x.template.cmp(&y.template))
+                .then(x.unit.cmp(&y.unit));
+        });
+        return report;
+    }
+
+    // Print report as table, one section per kind of difference
+    pub fn print(&self, out: &mut dyn Write, colors: &color::Colors) -> io::Result<()> {
+        writeln!(out, "A : {}, {} entries", self.a, self.entries_a)?;
+        writeln!(out, "B : {}, {} entries", self.b, self.entries_b)?;
+
+        let sections = [
+            ("New errors in B", &self.new_errors),
+            ("Only in A", &self.only_a),
+            ("Only in B", &self.only_b),
+            ("Count changes, A -> B", &self.changed),
+        ];
+        let unit_width = sections
+            .iter()
+            .flat_map(|(_, templates)| templates.iter())
+            .map(|t| t.unit.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (title, templates) in sections.iter() {
+            writeln!(out)?;
+            writeln!(
+                out,
+                "{}",
+                colors.header(&format!("{} ({}) :", title, templates.len()))
+            )?;
+            for template in templates.iter() {
+                print_template(out, template, unit_width, colors)?;
+            }
+        }
+
+        writeln!(out)?;
+        let units = [
+            ("Units started only in A", &self.started_only_a),
+            ("Units started only in B", &self.started_only_b),
+            ("Units failed only in A", &self.failed_only_a),
+            ("Units failed only in B", &self.failed_only_b),
+        ];
+        for (title, names) in units.iter() {
+            if names.is_empty() {
+                writeln!(out, "{} : -", title)?;
+                continue;
+            }
+            let names: Vec<String> = names.iter().map(|u| colors.unit(u)).collect();
+            writeln!(out, "{} : {}", title, names.join(", "))?;
+        }
+        return Ok(());
+    }
+}
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+fn template_diff(
+    key: &(String, String),
+    a: Option<&TemplateCount>,
+    b: Option<&TemplateCount>,
+) -> TemplateDiff {
+    let priority = match (a.and_then(|t| t.priority), b.and_then(|t| t.priority)) {
+        (Some(p), Some(e)) => Some(p.min(e)),
+        (p, e) => p.or(e),
+    };
+    return TemplateDiff {
+        template: key.0.clone(),
+        unit: key.1.clone(),
+        priority,
+        count_a: a.map_or(0, |t| t.count),
+        count_b: b.map_or(0, |t| t.count),
+        example: b.or(a).map_or(String::new(), |t| t.example.clone()),
+    };
+}
+
+// Sort templates by severity, then by number of messages. Templates without priority last
+fn sort_by_severity(templates: &mut [TemplateDiff]) {
+    templates.sort_by(|x, y| {
+        let x_priority = x.priority.unwrap_or(u32::MAX);
+        let y_priority = y.priority.unwrap_or(u32::MAX);
+        return x_priority
+            .cmp(&y_priority)
+            .then((y.count_a + y.count_b).cmp(&(x.count_a + x.count_b)))
+            .then(x.template.cmp(&y.template))
+            .then(x.unit.cmp(&y.unit));
+    });
+}
+
+// Print template with priority, counts and unit. Only the first line of multi-line
+// templates is printed
+fn print_template(
+    out: &mut dyn Write,
+    template: &TemplateDiff,
+    unit_width: usize,
+    colors: &color::Colors,
+) -> io::Result<()> {
+    let priority = opt::priority_name(template.priority);
+    let counts = format!("{} -> {}", template.count_a, template.count_b);
+    writeln!(
+        out,
+        "    {} {: >12}  {}  {}",
+        colors.priority(&format!("{: <7}", priority), template.priority),
+        counts,
+        colors.unit(&format!("{: <width$}", template.unit, width = unit_width)),
+        template.template.lines().next().unwrap_or("")
+    )?;
+    return Ok(());
+}
@@ -0,0 +1,154 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: digest.rs
+// File Description: Grouping of similar log messages into message templates
+// License: MIT
+//------------------------------------------------------------------------------
+
+use std::collections::{BTreeSet, HashMap};
+
+use regex::{Captures, Regex};
+use serde::{Serialize, Serializer};
+
+use crate::parser::{self, FormattedLogEntry};
+
+//------------------------------------------------------------------------------
+// Message normalizer type
+//------------------------------------------------------------------------------
+
+// Variable parts of messages and their placeholders, in the order they are masked.
+// Patterns with a "pre" group keep the preceding character, and long hex values
+// without letters are masked as numbers
+const MASKS: [(&str, &str); 7] = [
+    (
+        r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+        "<uuid>",
+    ),
+    (r"\b[0-9a-fA-F]{2}(?::[0-9a-fA-F]{2}){5}\b", "<mac>"),
+    (r"\b\d{1,3}(?:\.\d{1,3}){3}(?::\d+)?\b", "<ip>"),
+    (
+        r"\b(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}\b|\b(?:[0-9a-fA-F]{1,4}:){1,6}:(?:[0-9a-fA-F]{1,4}:?){0,6}[0-9a-fA-F]{1,4}\b",
+        "<ip>",
+    ),
+    (r#"(?P<pre>^|[\s=("'\[])/[^\s"'(),;\]]+"#, "<path>"),
+    (r"\b(?:0x[0-9a-fA-F]+|[0-9a-fA-F]{8,})\b", "<hex>"),
+    (r"(?P<pre>^|[^A-Za-z0-9_.])\d+(?:\.\d+)*", "<num>"),
+];
+
+// Masks variable parts of messages, e.g. numbers and paths, so that messages that only
+// differ in those parts get the same template
+pub struct Normalizer {
+    masks: Vec<(Regex, &'static str)>,
+}
+
+impl Normalizer {
+    pub fn new() -> Normalizer {
+        let masks = MASKS
+            .iter()
+            .map(|(pattern, placeholder)| (Regex::new(pattern).unwrap(), *placeholder))
+            .collect();
+        return Normalizer { masks };
+    }
+
+    // Get template of message
+    pub fn template(&self, message: &str) -> String {
+        let mut template = message.to_string();
+        for (regex, placeholder) in &self.masks {
+            template = regex
+                .replace_all(&template, |caps: &Captures| {
+                    let pre = caps.name("pre").map_or("", |m| m.as_str());
+                    let value = &caps[0][pre.len()..];
+                    if *placeholder == "<hex>" && value.chars().all(|c| c.is_ascii_digit()) {
+                        return format!("{}<num>", pre);
+                    }
+                    return format!("{}{}", pre, placeholder);
+                })
+                .into_owned();
+        }
+        return template;
+    }
+}
+
+//------------------------------------------------------------------------------
+// Digest types
+//------------------------------------------------------------------------------
+
+// Messages of one template
+#[derive(Debug, Serialize)]
+pub struct TemplateInfo {
+    pub template: String,
+    pub count: usize,
+    // Most severe priority of the messages
+    pub priority: Option<u32>,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub first: i64,
+    #[serde(serialize_with = "serialize_timestamp")]
+    pub last: i64,
+    pub units: BTreeSet<String>,
+    // First message of the template
+    pub example: String,
+}
+
+// Message templates of a set of log entries
+pub struct Digest {
+    normalizer: Normalizer,
+    templates: HashMap<String, TemplateInfo>,
+}
+
+impl Digest {
+    pub fn new() -> Digest {
+        return Digest {
+            normalizer: Normalizer::new(),
+            templates: HashMap::new(),
+        };
+    }
+
+    // Add entry to the template of its message
+    pub fn add(&mut self, entry: &FormattedLogEntry) {
+        let template = self.normalizer.template(&entry.message);
+        let info = self
+            .templates
+            .entry(template.clone())
+            .or_insert_with(|| TemplateInfo {
+                template,
+                count: 0,
+                priority: entry.priority,
+                first: entry.realtime,
+                last: entry.realtime,
+                units: BTreeSet::new(),
+                example: entry.message.clone(),
+            });
+
+        info.count += 1;
+        info.priority = match (info.priority, entry.priority) {
+            (Some(p), Some(e)) => Some(p.min(e)),
+            (p, e) => p.or(e),
+        };
+        info.first = info.first.min(entry.realtime);
+        info.last = info.last.max(entry.realtime);
+        if !info.units.contains(&entry.unit) {
+            info.units.insert(entry.unit.clone());
+        }
+    }
+
+    // Templates sorted by severity, then by number of messages, first occurrence and
+    // template. Templates without priority are sorted last
+    pub fn templates(self) -> Vec<TemplateInfo> {
+        let mut templates: Vec<TemplateInfo> = self.templates.into_values().collect();
+        templates.sort_by(|a, b| {
+            let a_priority = a.priority.unwrap_or(u32::MAX);
+            let b_priority = b.priority.unwrap_or(u32::MAX);
+            return a_priority
+                .cmp(&b_priority)
+                .then(b.count.cmp(&a.count))
+                .then(a.first.cmp(&b.first))
+                .then(a.template.cmp(&b.template));
+        });
+        return templates;
+    }
+}
+
+// Serialize realtime timestamp as date and time, as in table output
+fn serialize_timestamp<S: Serializer>(timestamp: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    return serializer.serialize_str(&parser::format_timestamp(Some(*timestamp)));
+}
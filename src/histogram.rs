@@ -0,0 +1,317 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: histogram.rs
+// File Description: Histogram of log entries over time
+// License: MIT
+//------------------------------------------------------------------------------
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::color;
+use crate::parser::{self, FormattedLogEntry};
+
+//------------------------------------------------------------------------------
+// Histogram constants
+//------------------------------------------------------------------------------
+
+// Priority groups of stacked bars: err and above, warning, notice, info and below
+const GROUP_NAMES: [&str; 4] = ["err", "warning", "notice", "info"];
+// Priority used to color each group
+const GROUP_PRIORITIES: [Option<u32>; 4] = [Some(3), Some(4), Some(5), None];
+const UNICODE_BARS: [char; 4] = ['█', '▓', '▒', '░'];
+const ASCII_BARS: [char; 4] = ['#', '=', '+', '.'];
+// Partial blocks of unstacked unicode bars, in eighths of a character
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+// Intervals to choose from for automatic interval, in seconds
+const AUTO_INTERVALS: [i64; 15] = [
+    1,
+    5,
+    10,
+    30,
+    60,
+    5 * 60,
+    10 * 60,
+    30 * 60,
+    3600,
+    3 * 3600,
+    6 * 3600,
+    12 * 3600,
+    86400,
+    7 * 86400,
+    30 * 86400,
+];
+// Max number of buckets with automatic interval
+const AUTO_BUCKETS: i64 = 40;
+// Max number of buckets with given interval
+const MAX_BUCKETS: i64 = 10000;
+
+//------------------------------------------------------------------------------
+// Histogram type
+//------------------------------------------------------------------------------
+
+// Entry counts per priority group in consecutive time buckets
+pub struct Histogram {
+    // Bucket length and start of first bucket, in microseconds
+    interval: i64,
+    start: i64,
+    buckets: Vec<[usize; 4]>,
+    entries: usize,
+}
+
+// Entry counts of one bucket per priority group, for json output
+#[derive(Debug, Serialize)]
+pub struct BucketCounts {
+    pub start: String,
+    pub total: usize,
+    pub err: usize,
+    pub warning: usize,
+    pub notice: usize,
+    pub info: usize,
+}
+
+// Complete histogram, for json output
+#[derive(Debug, Serialize)]
+pub struct HistogramReport {
+    pub entries: usize,
+    pub interval: String,
+    pub buckets: Vec<BucketCounts>,
+}
+
+impl Histogram {
+    // Count entries in buckets of given interval (microseconds),
+    // or an interval giving at most AUTO_BUCKETS buckets if not given
+    pub fn new(
+        entries: &[&FormattedLogEntry],
+        interval: Option<u64>,
+    ) -> Result<Histogram, failure::Error> {
+        let first = entries.iter().map(|e| e.realtime).min().unwrap_or(0);
+        let last = entries.iter().map(|e| e.realtime).max().unwrap_or(0);
+
+        let interval = match interval {
+            Some(i) => i as i64,
+            None => auto_interval(first, last),
+        };
+        let start = first.div_euclid(interval) * interval;
+        let number = match entries.len() {
+            0 => 0,
+            _ => (last - start) / interval + 1,
+        };
+        if number > MAX_BUCKETS {
+            bail!(
+                "Interval {} gives {} buckets for the time range of entries, max is {}",
+                format_interval(interval),
+                number,
+                MAX_BUCKETS
+            );
+        }
+
+        let mut buckets = vec![[0usize; 4]; number as usize];
+        for entry in entries {
+            let bucket = ((entry.realtime - start) / interval) as usize;
+            buckets[bucket][group(entry.priority)] += 1;
+        }
+
+        return Ok(Histogram {
+            interval,
+            start,
+            buckets,
+            entries: entries.len(),
+        });
+    }
+
+    // Print histogram as bar chart, one bucket per line. Bars are stacked by priority
+    // group if stacked is set, and drawn with ascii characters if ascii is set
+    pub fn print(
+        &self,
+        out: &mut dyn Write,
+        width: usize,
+        stacked: bool,
+        ascii: bool,
+        colors: &color::Colors,
+    ) -> io::Result<()> {
+        let bar_chars = if ascii { ASCII_BARS } else { UNICODE_BARS };
+        if stacked {
+            let legend: Vec<String> = (0..GROUP_NAMES.len())
+                .map(|g| {
+                    let bar = colors.priority(&bar_chars[g].to_string(), GROUP_PRIORITIES[g]);
+                    format!("{} {}", bar, GROUP_NAMES[g])
+                })
+                .collect();
+            writeln!(out, "{}", legend.join("  "))?;
+        }
+
+        let totals: Vec<usize> = self.buckets.iter().map(|b| b.iter().sum()).collect();
+        let max = totals.iter().copied().max().unwrap_or(0).max(1);
+        let count_width = max.to_string().len();
+        let time_format = label_format(self.interval);
+        let label_width = self.label(0, time_format).chars().count();
+        let bar_width = width.saturating_sub(label_width + count_width + 3).max(10);
+
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            let (bar, length) = if stacked {
+                stacked_bar(bucket, max, bar_width, &bar_chars, colors)
+            } else if ascii {
+                scaled_bar(totals[index], max, bar_width, ASCII_BARS[0])
+            } else {
+                unicode_bar(totals[index], max, bar_width)
+            };
+
+            // Pad by displayed length, colored bars contain escape sequences
+            writeln!(
+                out,
+                "{} │{}{} {: >count_width$}",
+                self.label(index, time_format),
+                bar,
+                " ".repeat(bar_width.saturating_sub(length)),
+                totals[index],
+                count_width = count_width
+            )?;
+        }
+        return Ok(());
+    }
+
+    // Histogram with bucket start times as in the bar chart labels
+    pub fn report(&self) -> HistogramReport {
+        let time_format = label_format(self.interval);
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(index, bucket)| BucketCounts {
+                start: self.label(index, time_format),
+                total: bucket.iter().sum(),
+                err: bucket[0],
+                warning: bucket[1],
+                notice: bucket[2],
+                info: bucket[3],
+            })
+            .collect();
+        return HistogramReport {
+            entries: self.entries,
+            interval: self.interval(),
+            buckets,
+        };
+    }
+
+    pub fn entries(&self) -> usize {
+        return self.entries;
+    }
+
+    // Interval as text, e.g. "5m"
+    pub fn interval(&self) -> String {
+        return format_interval(self.interval);
+    }
+
+    // Start time of bucket as text
+    fn label(&self, index: usize, time_format: &str) -> String {
+        let time = self.start + index as i64 * self.interval;
+        return parser::format_time(time, time_format);
+    }
+}
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+// Priority group of entry, entries without priority are counted as info
+fn group(priority: Option<u32>) -> usize {
+    match priority {
+        Some(0..=3) => return 0,
+        Some(4) => return 1,
+        Some(5) => return 2,
+        _ => return 3,
+    }
+}
+
+// Smallest automatic interval giving at most AUTO_BUCKETS buckets, in microseconds
+fn auto_interval(first: i64, last: i64) -> i64 {
+    let range = last - first;
+    for interval in AUTO_INTERVALS.iter() {
+        if range / (interval * 1000000) < AUTO_BUCKETS {
+            return interval * 1000000;
+        }
+    }
+    return AUTO_INTERVALS[AUTO_INTERVALS.len() - 1] * 1000000;
+}
+
+// Format interval with the largest unit that divides it, e.g. "90s" or "2h"
+fn format_interval(interval: i64) -> String {
+    let units: [(&str, i64); 6] = [
+        ("d", 86400000000),
+        ("h", 3600000000),
+        ("m", 60000000),
+        ("s", 1000000),
+        ("ms", 1000),
+        ("us", 1),
+    ];
+    for (unit, length) in units.iter() {
+        if interval % length == 0 {
+            return format!("{}{}", interval / length, unit);
+        }
+    }
+    return format!("{}us", interval);
+}
+
+// Time format of bucket labels, with the precision of the interval
+fn label_format(interval: i64) -> &'static str {
+    if interval % 86400000000 == 0 {
+        return "%Y-%m-%d";
+    } else if interval % 60000000 == 0 {
+        return "%Y-%m-%d %H:%M";
+    } else if interval % 1000000 == 0 {
+        return "%Y-%m-%d %H:%M:%S";
+    }
+    return "%Y-%m-%d %H:%M:%S%.3f";
+}
+
+// Bar of count scaled to width, at least one character for non-zero counts.
+// Bars are returned with their displayed length
+fn scaled_bar(count: usize, max: usize, width: usize, bar_char: char) -> (String, usize) {
+    let mut length = (count * width + max / 2) / max;
+    if count > 0 {
+        length = length.max(1);
+    }
+    return (bar_char.to_string().repeat(length), length);
+}
+
+// Bar of count scaled to width in eighths of a character
+fn unicode_bar(count: usize, max: usize, width: usize) -> (String, usize) {
+    let mut eighths = (count * width * 8 + max / 2) / max;
+    if count > 0 {
+        eighths = eighths.max(1);
+    }
+    let mut bar = UNICODE_BARS[0].to_string().repeat(eighths / 8);
+    let partial = eighths % 8;
+    if partial > 0 {
+        bar.push(PARTIAL_BLOCKS[partial]);
+    }
+    return (bar, eighths.div_ceil(8));
+}
+
+// Bar with a segment per priority group, segment ends are rounded from the cumulative
+// count so the bar length matches the total count
+fn stacked_bar(
+    bucket: &[usize; 4],
+    max: usize,
+    width: usize,
+    bar_chars: &[char; 4],
+    colors: &color::Colors,
+) -> (String, usize) {
+    let mut bar = String::new();
+    let mut cumulative = 0;
+    let mut end = 0;
+    for (g, count) in bucket.iter().enumerate() {
+        if *count == 0 {
+            continue;
+        }
+        cumulative += count;
+        let start = end;
+        end = ((cumulative * width + max / 2) / max).max(start + 1);
+        let segment = bar_chars[g].to_string().repeat(end - start);
+        bar.push_str(&colors.priority(&segment, GROUP_PRIORITIES[g]));
+    }
+    return (bar, end);
+}
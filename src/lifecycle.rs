@@ -0,0 +1,390 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: lifecycle.rs
+// File Description: Unit lifecycle events from systemd messages
+// License: MIT
+//------------------------------------------------------------------------------
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::color;
+use crate::parser::{self, FormattedLogEntry, LogEntry};
+
+//------------------------------------------------------------------------------
+// systemd message IDs, see the systemd message catalog
+//------------------------------------------------------------------------------
+
+const MESSAGE_ID_STARTING: &str = "7d4958e842da4a758f6c1cdc7b36dcc5";
+const MESSAGE_ID_STARTED: &str = "39f53479d3a045ac8e11786248231fbf";
+const MESSAGE_ID_STOPPING: &str = "de5b426a63be47a7b6ac3eaac82e2f6f";
+const MESSAGE_ID_STOPPED: &str = "9d1aaa27d60140bd96365438aad20286";
+const MESSAGE_ID_FAILED: &str = "be02cf6855d2428ba40df7e9d022f03d";
+const MESSAGE_ID_FAILURE_RESULT: &str = "d9b373ed55a64feb8242e02dbe79a49c";
+const MESSAGE_ID_PROCESS_EXIT: &str = "98e322203f7a4ed290d09fe03c09fe15";
+const MESSAGE_ID_RESTART_SCHEDULED: &str = "5eb03494b6584870a536b337290809b3";
+const MESSAGE_ID_SUCCESS: &str = "7ad2d189f7e94e70a38c781354912448";
+
+// Number of scheduled restarts in a boot reported as restart loop
+const RESTART_LOOP_MIN: usize = 3;
+
+//------------------------------------------------------------------------------
+// Unit event enum type
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnitEvent {
+    Starting,
+    Started,
+    Stopping,
+    Stopped,
+    Deactivated,
+    Exited,
+    // Unit failed, with the result of the failure
+    Failed,
+    // Start job of unit failed, logged in addition to the failure
+    StartFailed,
+    RestartScheduled,
+    StartLimitHit,
+}
+
+impl UnitEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            UnitEvent::Starting => return "starting",
+            UnitEvent::Started => return "started",
+            UnitEvent::Stopping => return "stopping",
+            UnitEvent::Stopped => return "stopped",
+            UnitEvent::Deactivated => return "deactivated",
+            UnitEvent::Exited => return "exited",
+            UnitEvent::Failed => return "failed",
+            UnitEvent::StartFailed => return "start-failed",
+            UnitEvent::RestartScheduled => return "restart",
+            UnitEvent::StartLimitHit => return "start-limit",
+        }
+    }
+
+    // State of unit after event
+    pub fn state(self) -> &'static str {
+        match self {
+            UnitEvent::Starting => return "starting",
+            UnitEvent::Started => return "started",
+            UnitEvent::Stopping => return "stopping",
+            UnitEvent::Stopped | UnitEvent::Deactivated => return "stopped",
+            UnitEvent::Exited => return "exited",
+            UnitEvent::Failed | UnitEvent::StartFailed | UnitEvent::StartLimitHit => {
+                return "failed"
+            }
+            UnitEvent::RestartScheduled => return "restarting",
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Public functions
+//------------------------------------------------------------------------------
+
+// Get unit and lifecycle event of a systemd message, if the message is one.
+// Messages are identified by MESSAGE_ID, or by text for messages without ID
+pub fn unit_event(entry: &LogEntry) -> Option<(String, UnitEvent)> {
+    let message = entry.MESSAGE.as_deref().unwrap_or("");
+    let event = match entry.field("MESSAGE_ID").as_deref() {
+        Some(MESSAGE_ID_STARTING) => UnitEvent::Starting,
+        Some(MESSAGE_ID_STARTED) => UnitEvent::Started,
+        Some(MESSAGE_ID_STOPPING) => UnitEvent::Stopping,
+        Some(MESSAGE_ID_STOPPED) => UnitEvent::Stopped,
+        Some(MESSAGE_ID_SUCCESS) => UnitEvent::Deactivated,
+        Some(MESSAGE_ID_PROCESS_EXIT) => UnitEvent::Exited,
+        // Dependency failures are logged as failed start jobs, without a failure result
+        Some(MESSAGE_ID_FAILED) if message.starts_with("Dependency failed for ") => {
+            UnitEvent::Failed
+        }
+        Some(MESSAGE_ID_FAILED) => UnitEvent::StartFailed,
+        Some(MESSAGE_ID_FAILURE_RESULT) => UnitEvent::Failed,
+        Some(MESSAGE_ID_RESTART_SCHEDULED) => UnitEvent::RestartScheduled,
+        Some(_) => return None,
+        None if entry.SYSLOG_IDENTIFIER.as_deref() != Some("systemd") => return None,
+        None => text_event(message)?,
+    };
+
+    // Unit of system and user manager messages, or name from message text
+    let unit = match entry.UNIT.clone().or_else(|| entry.field("USER_UNIT")) {
+        Some(unit) => unit,
+        None => text_unit(message)?,
+    };
+    return Some((unit, event));
+}
+
+//------------------------------------------------------------------------------
+// Unit report types
+//------------------------------------------------------------------------------
+
+// Lifecycle event of a unit
+#[derive(Debug, Serialize)]
+pub struct TimelineEvent {
+    #[serde(skip)]
+    pub realtime: i64,
+    pub time: String,
+    pub event: UnitEvent,
+    pub message: String,
+}
+
+// Lifecycle of a unit in one boot
+#[derive(Debug, Serialize)]
+pub struct UnitTimeline {
+    pub unit: String,
+    pub starts: usize,
+    pub stops: usize,
+    pub failures: usize,
+    pub restarts: usize,
+    pub state: &'static str,
+    pub restart_loop: bool,
+    pub never_started: bool,
+    pub events: Vec<TimelineEvent>,
+}
+
+impl UnitTimeline {
+    fn new(unit: String) -> UnitTimeline {
+        return UnitTimeline {
+            unit,
+            starts: 0,
+            stops: 0,
+            failures: 0,
+            restarts: 0,
+            state: "",
+            restart_loop: false,
+            never_started: false,
+            events: Vec::new(),
+        };
+    }
+
+    // Count events and set state and flags, when all events are added. A failed start
+    // is logged as both failure and failed start job, only the failure is counted.
+    // Units failed without a failure event, e.g. only a failed start job, count one failure
+    fn finish(&mut self) {
+        self.events.sort_by_key(|e| e.realtime);
+        let mut start_limit_hit = false;
+        let mut attempted = false;
+        for event in &self.events {
+            match event.event {
+                UnitEvent::Started => self.starts += 1,
+                UnitEvent::Stopped => self.stops += 1,
+                UnitEvent::Failed => self.failures += 1,
+                UnitEvent::RestartScheduled => self.restarts += 1,
+                UnitEvent::StartLimitHit => start_limit_hit = true,
+                _ => {}
+            }
+            if matches!(event.event, UnitEvent::Starting | UnitEvent::StartFailed) {
+                attempted = true;
+            }
+        }
+        if let Some(last) = self.events.last() {
+            self.state = last.event.state();
+        }
+        if self.state == "failed" && self.failures == 0 {
+            self.failures = 1;
+        }
+
+        self.restart_loop = start_limit_hit || self.restarts >= RESTART_LOOP_MIN;
+        // Units only stopped, e.g. at shutdown, may have been started before the log begins
+        self.never_started = attempted && self.starts == 0;
+    }
+
+    // Problems of unit as text, empty if none
+    fn flags(&self) -> String {
+        let mut flags = Vec::new();
+        if self.restart_loop {
+            flags.push("restart loop");
+        }
+        if self.never_started {
+            flags.push("never started");
+        }
+        return flags.join(", ");
+    }
+}
+
+// Unit lifecycles of one boot
+#[derive(Debug, Serialize)]
+pub struct BootUnits {
+    pub boot: String,
+    pub host: String,
+    pub units: Vec<UnitTimeline>,
+}
+
+impl BootUnits {
+    pub fn new(boot: String, host: String) -> BootUnits {
+        return BootUnits {
+            boot,
+            host,
+            units: Vec::new(),
+        };
+    }
+
+    // Add entry to the timeline of its unit, if it is a lifecycle event
+    pub fn add(&mut self, log_entry: &LogEntry, entry: &FormattedLogEntry) {
+        let (unit, event) = match unit_event(log_entry) {
+            Some(e) => e,
+            None => return,
+        };
+        let index = match self.units.iter().position(|u| u.unit == unit) {
+            Some(index) => index,
+            None => {
+                self.units.push(UnitTimeline::new(unit));
+                self.units.len() - 1
+            }
+        };
+        self.units[index].events.push(TimelineEvent {
+            realtime: entry.realtime,
+            time: parser::format_timestamp(Some(entry.realtime)),
+            event,
+            message: entry.message.clone(),
+        });
+    }
+
+    // Set state and flags of units, units with problems first
+    pub fn finish(&mut self) {
+        for unit in self.units.iter_mut() {
+            unit.finish();
+        }
+        self.units.sort_by(|a, b| {
+            let a_ok = !(a.restart_loop || a.never_started);
+            let b_ok = !(b.restart_loop || b.never_started);
+            return a_ok.cmp(&b_ok).then(a.unit.cmp(&b.unit));
+        });
+    }
+
+    // Print summary table of units, followed by the timeline of each unit
+    pub fn print(&self, out: &mut dyn Write, colors: &color::Colors) -> io::Result<()> {
+        if self.units.is_empty() {
+            writeln!(out, "No unit lifecycle messages")?;
+            return Ok(());
+        }
+
+        let width = self
+            .units
+            .iter()
+            .map(|u| u.unit.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        let header = format!(
+            "{: <width$}  {: >6}  {: >5}  {: >8}  {: >8}  {: <10}  PROBLEMS",
+            "UNIT",
+            "STARTS",
+            "STOPS",
+            "FAILURES",
+            "RESTARTS",
+            "STATE",
+            width = width
+        );
+        writeln!(out, "{}", colors.header(&header))?;
+        for unit in &self.units {
+            let state = format!("{: <10}", unit.state);
+            let state = match unit.state {
+                "failed" => colors.priority(&state, Some(3)),
+                _ => state,
+            };
+            let line = format!(
+                "{}  {: >6}  {: >5}  {: >8}  {: >8}  {}  {}",
+                colors.unit(&format!("{: <width$}", unit.unit, width = width)),
+                unit.starts,
+                unit.stops,
+                unit.failures,
+                unit.restarts,
+                state,
+                colors.priority(&unit.flags(), Some(3))
+            );
+            writeln!(out, "{}", line.trim_end())?;
+        }
+
+        for unit in &self.units {
+            writeln!(out)?;
+            writeln!(out, "{} :", colors.unit(&unit.unit))?;
+            for event in &unit.events {
+                let priority = match event.event {
+                    UnitEvent::Failed | UnitEvent::StartFailed | UnitEvent::StartLimitHit => {
+                        Some(3)
+                    }
+                    UnitEvent::Exited | UnitEvent::RestartScheduled => Some(4),
+                    _ => None,
+                };
+                writeln!(
+                    out,
+                    "    {}  {}  {}",
+                    event.time,
+                    colors.priority(&format!("{: <12}", event.event.name()), priority),
+                    event.message.lines().next().unwrap_or("")
+                )?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+// Lifecycle event of message text, e.g. "Started nginx.service - nginx."
+fn text_event(message: &str) -> Option<UnitEvent> {
+    // Oneshot services are reported as "Finished" when started
+    if message.starts_with("Started ") || message.starts_with("Finished ") {
+        return Some(UnitEvent::Started);
+    }
+    if message.starts_with("Starting ") {
+        return Some(UnitEvent::Starting);
+    }
+    if message.starts_with("Stopping ") {
+        return Some(UnitEvent::Stopping);
+    }
+    if message.starts_with("Stopped ") {
+        return Some(UnitEvent::Stopped);
+    }
+    if message.starts_with("Failed to start ") {
+        return Some(UnitEvent::StartFailed);
+    }
+    if message.starts_with("Dependency failed for ") || message.contains(": Failed with result ") {
+        return Some(UnitEvent::Failed);
+    }
+    if message.contains(": Main process exited") {
+        return Some(UnitEvent::Exited);
+    }
+    if message.contains(": Scheduled restart job") {
+        return Some(UnitEvent::RestartScheduled);
+    }
+    if message.contains(": Start request repeated too quickly") {
+        return Some(UnitEvent::StartLimitHit);
+    }
+    if message.contains(": Deactivated successfully") {
+        return Some(UnitEvent::Deactivated);
+    }
+    return None;
+}
+
+// Unit name, or description if the message has no unit name, of message text
+fn text_unit(message: &str) -> Option<String> {
+    let prefixes = [
+        "Started ",
+        "Finished ",
+        "Starting ",
+        "Stopping ",
+        "Stopped ",
+        "Failed to start ",
+        "Dependency failed for ",
+    ];
+    let subject = match prefixes.iter().find_map(|p| message.strip_prefix(p)) {
+        Some(subject) => subject,
+        // "<unit>: <event>" messages
+        None => return message.split_once(": ").map(|(unit, _)| unit.to_string()),
+    };
+
+    // Newer systemd versions print "<unit> - <description>."
+    let subject = subject.trim_end_matches('.');
+    let unit = match subject.split_once(" - ") {
+        Some((unit, _)) => unit,
+        None => subject,
+    };
+    return Some(unit.to_string());
+}
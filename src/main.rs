@@ -4,6 +4,9 @@
 // File Description: Jrnlvw main file
 // License: MIT
 //------------------------------------------------------------------------------
+// Explicit returns are used throughout the project
+#![allow(clippy::needless_return)]
+
 #[macro_use]
 extern crate clap;
 extern crate chrono;
@@ -11,12 +14,27 @@ extern crate serde;
 extern crate serde_json;
 #[macro_use]
 extern crate failure;
+extern crate flate2;
+extern crate ratatui;
+extern crate regex;
 
+use std::io::{self, Write};
 use std::process;
 
 mod cli;
+mod color;
+mod diff;
+mod digest;
+mod errno;
+mod histogram;
+mod lifecycle;
 mod opt;
+mod outfile;
+mod output;
+mod pager;
 mod parser;
+mod stats;
+mod viewer;
 
 //------------------------------------------------------------------------------
 
@@ -35,9 +53,10 @@ fn main() {
     if options.is_err() {
         process::exit(-1);
     }
+    let options = options.unwrap();
 
     // Parse logfile based on cli options
-    let parsed_file = match parser::ParsedLogfile::new(&options.unwrap()) {
+    let parsed_file = match parser::ParsedLogfile::new(&options) {
         Ok(f) => Ok(f),
         Err(err) => {
             eprintln!("Failed to parse logfile: {}", err);
@@ -46,13 +65,70 @@ fn main() {
     };
 
     if parsed_file.is_err() {
-        println!("Unable to continue...");
+        eprintln!("Unable to continue...");
         process::exit(-1);
     }
 
-    // Display logs
+    // Browse logs in the interactive viewer
     let logfile = parsed_file.unwrap();
-    logfile.print();
+    if options.interactive() {
+        if let Err(err) = viewer::run(logfile) {
+            eprintln!("Interactive viewer failed: {}", err);
+            process::exit(-1);
+        }
+        process::exit(0);
+    }
+
+    // Display logs, or write them to the output file
+    let result = match options.output_file() {
+        Some(path) => write_output_file(logfile, path),
+        None if options.pager() => write_pager(logfile),
+        None => write_stdout(logfile),
+    };
+
+    // Output closed by the reader, e.g. pager quit or piped to head, is not an error
+    if let Err(err) = result.as_ref() {
+        if err.kind() == io::ErrorKind::BrokenPipe {
+            process::exit(0);
+        }
+    }
+    if let Err(err) = result {
+        eprintln!("Failed to write output: {}", err);
+        process::exit(-1);
+    }
 
     process::exit(0);
 }
+
+// Write logs to stdout
+fn write_stdout(logfile: parser::ParsedLogfile) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    logfile.print(&mut out)?;
+    out.flush()?;
+    return Ok(());
+}
+
+// Write logs to pager, or to stdout if the pager could not be started
+fn write_pager(logfile: parser::ParsedLogfile) -> io::Result<()> {
+    let mut pager = match pager::Pager::start() {
+        Some(p) => p,
+        None => return write_stdout(logfile),
+    };
+    let result = logfile.print(pager.input());
+
+    // Wait for pager also if output failed, the user may still read the written output
+    let waited = pager.wait();
+    result?;
+    waited?;
+    return Ok(());
+}
+
+// Write logs to output file, the file is only replaced if all output was written
+fn write_output_file(logfile: parser::ParsedLogfile, path: &str) -> io::Result<()> {
+    let mut file = outfile::OutputFile::create(path)?;
+    logfile.print(&mut file)?;
+    file.finish()?;
+    eprintln!("Output written to '{}'", path);
+    return Ok(());
+}
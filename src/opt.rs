@@ -6,6 +6,13 @@
 //------------------------------------------------------------------------------
 
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, UTC};
+
+use crate::output::Template;
+use ratatui::crossterm::terminal;
+use regex::{Regex, RegexBuilder};
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
 use std::path::Path;
 //------------------------------------------------------------------------------
 // Command line interface flags
@@ -18,10 +25,224 @@ pub const BOOT_FILTER: &str = "boot";
 pub const UNIT_FILTER: &str = "unit";
 pub const KERNEL_FLAG: &str = "kernel";
 pub const NUM_OF_ENTRIES: &str = "number";
+pub const NUM_OF_LINES: &str = "lines";
+pub const LIMIT_OVERALL_FLAG: &str = "overall";
+pub const REVERSE_FLAG: &str = "reverse";
+pub const SORT_KEY: &str = "sort";
+pub const FLAT_FLAG: &str = "flat";
+pub const OUTPUT_MODE: &str = "output";
+pub const COLUMNS: &str = "columns";
+pub const FORMAT_TEMPLATE: &str = "format";
+pub const DETAILS_FLAG: &str = "details";
 pub const TIME_FROM: &str = "time-from";
 pub const TIME_TO: &str = "time-to";
 pub const DATE_FROM: &str = "date-from";
 pub const DATE_TO: &str = "date-to";
+pub const TIMESTAMP_MODE: &str = "timestamp";
+pub const PRECISE_FLAG: &str = "precise";
+pub const BOOT_TIME: &str = "boot-time";
+pub const CURSOR: &str = "cursor";
+pub const AFTER_CURSOR: &str = "after-cursor";
+pub const SHOW_CURSOR_FLAG: &str = "show-cursor";
+pub const CURSOR_FILE: &str = "cursor-file";
+pub const FACILITY_FILTER: &str = "facility";
+pub const TRANSPORT_FILTER: &str = "transport";
+pub const PID_FILTER: &str = "pid";
+pub const UID_FILTER: &str = "uid";
+pub const HOST_FILTER: &str = "host";
+pub const MACHINE_FILTER: &str = "machine";
+pub const LIST_HOSTS_FLAG: &str = "list-hosts";
+pub const GROUP_BY_HOST_FLAG: &str = "group-by-host";
+pub const COLOR_MODE: &str = "color";
+pub const OUTPUT_FILE: &str = "output-file";
+pub const NO_PAGER_FLAG: &str = "no-pager";
+pub const GREP_FILTER: &str = "grep";
+pub const INTERACTIVE_FLAG: &str = "interactive";
+pub const TRUNCATE_FLAG: &str = "truncate";
+pub const WRAP_FLAG: &str = "wrap";
+pub const HIDE_COLUMNS: &str = "hide";
+pub const STATS_FLAG: &str = "stats";
+pub const TOP_COUNT: &str = "top";
+pub const HISTOGRAM_FLAG: &str = "histogram";
+pub const HISTOGRAM_INTERVAL: &str = "interval";
+pub const STACKED_FLAG: &str = "stacked";
+pub const ASCII_FLAG: &str = "ascii";
+pub const DIGEST_FLAG: &str = "digest";
+pub const DIFF_FLAG: &str = "diff";
+pub const UNITS_REPORT_FLAG: &str = "units-report";
+
+// Table columns that can be hidden
+pub const HIDE_SEQ: &str = "seq";
+pub const HIDE_TIME: &str = "time";
+pub const HIDE_PRIORITY: &str = "priority";
+pub const HIDE_UNIT: &str = "unit";
+pub const HIDE_PID: &str = "pid";
+pub const HIDEABLE_COLUMNS: [&str; 5] = [HIDE_SEQ, HIDE_TIME, HIDE_PRIORITY, HIDE_UNIT, HIDE_PID];
+
+//------------------------------------------------------------------------------
+// Computed output columns, in addition to journal fields
+//------------------------------------------------------------------------------
+
+pub const COLUMN_TIME: &str = "time";
+pub const COLUMN_SEQNUM: &str = "seqnum";
+pub const COLUMN_BOOT: &str = "boot";
+pub const COLUMN_PRIORITY: &str = "priority";
+pub const COLUMN_UNIT: &str = "unit";
+pub const COMPUTED_COLUMNS: [&str; 5] = [
+    COLUMN_TIME,
+    COLUMN_SEQNUM,
+    COLUMN_BOOT,
+    COLUMN_PRIORITY,
+    COLUMN_UNIT,
+];
+
+//------------------------------------------------------------------------------
+// Output mode enum type
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputMode {
+    // Columns, with boot headers
+    Table,
+    // Line delimited json, one journal entry per line
+    Json,
+    // Comma separated values, with header row
+    Csv,
+    // Tab separated values, with header row
+    Tsv,
+    // User defined format template
+    Template,
+    // journalctl text formats
+    Short,
+    ShortIso,
+    ShortPrecise,
+    ShortMonotonic,
+    WithUnit,
+    Cat,
+    Verbose,
+}
+
+impl Default for OutputMode {
+    fn default() -> Self {
+        return OutputMode::Table;
+    }
+}
+
+impl OutputMode {
+    // Check if output mode is one of the journalctl text formats, separated by boot lines
+    pub fn is_journal_text(self) -> bool {
+        match self {
+            OutputMode::Short
+            | OutputMode::ShortIso
+            | OutputMode::ShortPrecise
+            | OutputMode::ShortMonotonic
+            | OutputMode::WithUnit
+            | OutputMode::Cat
+            | OutputMode::Verbose => return true,
+            _ => return false,
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Entry sort key enum type
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    // Realtime timestamp
+    Time,
+    // Sequence number from cursor
+    Seqnum,
+    // Priority, most severe first
+    Priority,
+}
+
+//------------------------------------------------------------------------------
+// Syslog priority (log level) names, indexed by level
+//------------------------------------------------------------------------------
+
+pub const PRIORITY_NAMES: [&str; 8] = [
+    "emerg", "alert", "crit", "err", "warning", "notice", "info", "debug",
+];
+
+//------------------------------------------------------------------------------
+// Syslog facility names, indexed by facility number
+//------------------------------------------------------------------------------
+
+pub const FACILITY_NAMES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+
+//------------------------------------------------------------------------------
+// Journal transport names (_TRANSPORT field values)
+//------------------------------------------------------------------------------
+
+pub const TRANSPORT_NAMES: [&str; 6] = ["audit", "driver", "syslog", "journal", "stdout", "kernel"];
+
+//------------------------------------------------------------------------------
+// Timestamp display mode enum type
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimestampMode {
+    // Wall clock time (UTC) of the entry
+    Realtime,
+    // Time since boot, from the monotonic timestamp (like dmesg)
+    Monotonic,
+    // Time since the previous entry of the same boot in the file, before sorting
+    // and entry selection
+    Delta,
+}
+
+impl Default for TimestampMode {
+    fn default() -> Self {
+        return TimestampMode::Realtime;
+    }
+}
+
+//------------------------------------------------------------------------------
+// Message line display mode enum type
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineMode {
+    // Message lines are printed in full length
+    Full,
+    // Message lines are cut at terminal width
+    Truncate,
+    // Message lines are wrapped at terminal width
+    Wrap,
+}
+
+impl Default for LineMode {
+    fn default() -> Self {
+        return LineMode::Full;
+    }
+}
 
 //------------------------------------------------------------------------------
 // File Command line options/argument struct type
@@ -29,17 +250,60 @@ pub const DATE_TO: &str = "date-to";
 
 #[derive(Debug, Default, Clone)]
 pub struct CliOptions {
-    logfile: String,
+    logfiles: Vec<String>,
     list_boots: bool,
-    log_level: u32,
+    list_hosts: bool,
+    group_by_host: bool,
+    log_level_from: u32,
+    log_level_to: u32,
     boot_filter: Vec<String>,
     unit_filter: Vec<String>,
     kernel_flag: bool,
+    facility_filter: Vec<u32>,
+    transport_filter: Vec<String>,
+    pid_filter: Vec<u32>,
+    uid_filter: Vec<(u32, u32)>,
+    host_filter: Vec<String>,
+    machine_filter: Vec<String>,
     num_of_entries: u32,
+    num_of_lines: u32,
+    limit_overall: bool,
+    reverse: bool,
+    sort_key: Option<SortKey>,
+    flat: bool,
+    output_mode: OutputMode,
+    columns: Vec<String>,
+    template: Template,
+    details: bool,
     start_time_filter: i64,
     stop_time_filter: i64,
     start_date_filter: i64,
     stop_date_filter: i64,
+    timestamp_mode: TimestampMode,
+    precise: bool,
+    boot_time_from: Option<u64>,
+    boot_time_to: Option<u64>,
+    cursor: Option<String>,
+    after_cursor: bool,
+    show_cursor: bool,
+    cursor_file: Option<String>,
+    color: bool,
+    output_file: Option<String>,
+    pager: bool,
+    grep: Option<Regex>,
+    interactive: bool,
+    line_mode: LineMode,
+    terminal_width: Option<usize>,
+    hidden_columns: Vec<String>,
+    stats: bool,
+    top_count: usize,
+    histogram: bool,
+    histogram_interval: Option<u64>,
+    stacked: bool,
+    ascii: bool,
+    digest: bool,
+    diff: bool,
+    units_report: bool,
 }
 
 //------------------------------------------------------------------------------
@@ -49,21 +313,35 @@ pub struct CliOptions {
 impl CliOptions {
     // Getters for cli options members
 
-    // Get path to logfile
-    pub fn logfile_path(&self) -> &Path {
-        return Path::new(&self.logfile);
+    // Get paths to logfiles
+    pub fn logfile_paths(&self) -> Vec<&Path> {
+        return self.logfiles.iter().map(Path::new).collect();
     }
 
-    pub fn logfile_name(&self) -> &String {
-        return &self.logfile;
+    pub fn logfile_names(&self) -> &Vec<String> {
+        return &self.logfiles;
     }
 
     pub fn list_boots(&self) -> bool {
         return self.list_boots;
     }
 
-    pub fn log_level(&self) -> u32 {
-        return self.log_level;
+    pub fn list_hosts(&self) -> bool {
+        return self.list_hosts;
+    }
+
+    pub fn group_by_host(&self) -> bool {
+        return self.group_by_host;
+    }
+
+    // Highest priority (lowest level) to print
+    pub fn log_level_from(&self) -> u32 {
+        return self.log_level_from;
+    }
+
+    // Lowest priority (highest level) to print
+    pub fn log_level_to(&self) -> u32 {
+        return self.log_level_to;
     }
 
     pub fn boot_filter(&self) -> &Vec<String> {
@@ -78,10 +356,77 @@ impl CliOptions {
         return self.kernel_flag;
     }
 
+    pub fn facility_filter(&self) -> &Vec<u32> {
+        return &self.facility_filter;
+    }
+
+    pub fn transport_filter(&self) -> &Vec<String> {
+        return &self.transport_filter;
+    }
+
+    pub fn pid_filter(&self) -> &Vec<u32> {
+        return &self.pid_filter;
+    }
+
+    // UID ranges (inclusive) to print
+    pub fn uid_filter(&self) -> &Vec<(u32, u32)> {
+        return &self.uid_filter;
+    }
+
+    pub fn host_filter(&self) -> &Vec<String> {
+        return &self.host_filter;
+    }
+
+    pub fn machine_filter(&self) -> &Vec<String> {
+        return &self.machine_filter;
+    }
+
     pub fn num_of_entries(&self) -> u32 {
         return self.num_of_entries;
     }
 
+    // Number of entries to print from the end of each boot
+    pub fn num_of_lines(&self) -> u32 {
+        return self.num_of_lines;
+    }
+
+    // If set, entry limits apply to all boots together instead of each boot
+    pub fn limit_overall(&self) -> bool {
+        return self.limit_overall;
+    }
+
+    pub fn reverse(&self) -> bool {
+        return self.reverse;
+    }
+
+    pub fn sort_key(&self) -> Option<SortKey> {
+        return self.sort_key;
+    }
+
+    // If set, entries from all boots are printed as a single timeline
+    pub fn flat(&self) -> bool {
+        return self.flat;
+    }
+
+    pub fn output_mode(&self) -> OutputMode {
+        return self.output_mode;
+    }
+
+    // Columns for csv/tsv output
+    pub fn columns(&self) -> &Vec<String> {
+        return &self.columns;
+    }
+
+    // Format template for template output
+    pub fn template(&self) -> &Template {
+        return &self.template;
+    }
+
+    // If set, all fields of each entry are printed below the entry in table output
+    pub fn details(&self) -> bool {
+        return self.details;
+    }
+
     pub fn start_time_filter(&self) -> i64 {
         return self.start_time_filter;
     }
@@ -97,6 +442,113 @@ impl CliOptions {
     pub fn stop_date_filter(&self) -> i64 {
         return self.stop_date_filter;
     }
+
+    pub fn timestamp_mode(&self) -> TimestampMode {
+        return self.timestamp_mode;
+    }
+
+    pub fn precise(&self) -> bool {
+        return self.precise;
+    }
+
+    // Start of monotonic range, microseconds since boot
+    pub fn boot_time_from(&self) -> Option<u64> {
+        return self.boot_time_from;
+    }
+
+    // End of monotonic range, microseconds since boot
+    pub fn boot_time_to(&self) -> Option<u64> {
+        return self.boot_time_to;
+    }
+
+    // Cursor to start displaying entries from
+    pub fn cursor(&self) -> Option<&String> {
+        return self.cursor.as_ref();
+    }
+
+    // If set, the entry matching the cursor is excluded from the output
+    pub fn after_cursor(&self) -> bool {
+        return self.after_cursor;
+    }
+
+    pub fn show_cursor(&self) -> bool {
+        return self.show_cursor;
+    }
+
+    pub fn cursor_file(&self) -> Option<&String> {
+        return self.cursor_file.as_ref();
+    }
+
+    pub fn color(&self) -> bool {
+        return self.color;
+    }
+
+    pub fn output_file(&self) -> Option<&String> {
+        return self.output_file.as_ref();
+    }
+
+    pub fn pager(&self) -> bool {
+        return self.pager;
+    }
+
+    pub fn grep(&self) -> Option<&Regex> {
+        return self.grep.as_ref();
+    }
+
+    pub fn interactive(&self) -> bool {
+        return self.interactive;
+    }
+
+    pub fn line_mode(&self) -> LineMode {
+        return self.line_mode;
+    }
+
+    pub fn hidden_columns(&self) -> &Vec<String> {
+        return &self.hidden_columns;
+    }
+
+    pub fn stats(&self) -> bool {
+        return self.stats;
+    }
+
+    // Number of most frequent identifiers listed in statistics
+    pub fn top_count(&self) -> usize {
+        return self.top_count;
+    }
+
+    pub fn histogram(&self) -> bool {
+        return self.histogram;
+    }
+
+    // Histogram bucket length in microseconds, None for automatic interval
+    pub fn histogram_interval(&self) -> Option<u64> {
+        return self.histogram_interval;
+    }
+
+    pub fn stacked(&self) -> bool {
+        return self.stacked;
+    }
+
+    pub fn ascii(&self) -> bool {
+        return self.ascii;
+    }
+
+    pub fn digest(&self) -> bool {
+        return self.digest;
+    }
+
+    pub fn diff(&self) -> bool {
+        return self.diff;
+    }
+
+    pub fn units_report(&self) -> bool {
+        return self.units_report;
+    }
+
+    // Width of terminal in columns, None if output is not written to a terminal
+    pub fn terminal_width(&self) -> Option<usize> {
+        return self.terminal_width;
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -107,65 +559,133 @@ impl CliOptions {
     // parse and set argument values from matches
     pub fn from_matches(matches: &clap::ArgMatches) -> Result<CliOptions, failure::Error> {
         // return object
-        let mut cli_opt = CliOptions::default();
-
-        cli_opt.logfile = matches.value_of(LOGFILE).unwrap().to_string();
+        let mut cli_opt = CliOptions {
+            logfiles: matches
+                .values_of(LOGFILE)
+                .unwrap()
+                .map(String::from)
+                .collect(),
+            ..Default::default()
+        };
 
         // set list-boots flag, if provided
-        if matches.is_present(LIST_BOOTS_FLAG) {
-            cli_opt.list_boots = true;
-        } else {
-            cli_opt.list_boots = false;
-        }
-
-        // set log level, if provided
-        if matches.is_present(LOG_LEVEL) {
-            let level = matches.value_of(LOG_LEVEL).unwrap();
-            let level = level.parse::<u32>()?;
-
-            // verify log level range
-            if level > 7 {
-                eprintln!(
-                    "Invalid log level: {}, default level DEBUG (7) will be used",
-                    level
-                );
-                let level = 7;
-                cli_opt.log_level = level;
-            } else {
-                cli_opt.log_level = level;
+        cli_opt.list_boots = matches.is_present(LIST_BOOTS_FLAG);
+        cli_opt.list_hosts = matches.is_present(LIST_HOSTS_FLAG);
+        cli_opt.group_by_host = matches.is_present(GROUP_BY_HOST_FLAG);
+
+        // set statistics flag, and number of top identifiers
+        cli_opt.stats = matches.is_present(STATS_FLAG);
+        cli_opt.top_count = match matches.value_of(TOP_COUNT) {
+            Some(num) => num.parse::<usize>()?,
+            None => 10,
+        };
+
+        // set histogram flag, interval and bar style
+        cli_opt.histogram = matches.is_present(HISTOGRAM_FLAG);
+        if let Some(input) = matches.value_of(HISTOGRAM_INTERVAL) {
+            let interval = parse_duration_us(input)?;
+            if interval == 0 {
+                bail!("Invalid histogram interval: {}, must be > 0", input);
             }
-        } else {
-            cli_opt.log_level = 7;
+            cli_opt.histogram_interval = Some(interval);
         }
+        cli_opt.stacked = matches.is_present(STACKED_FLAG);
+        cli_opt.ascii = matches.is_present(ASCII_FLAG);
+
+        cli_opt.digest = matches.is_present(DIGEST_FLAG);
+        cli_opt.diff = matches.is_present(DIFF_FLAG);
+        cli_opt.units_report = matches.is_present(UNITS_REPORT_FLAG);
+
+        // set log level range, all levels if not provided
+        cli_opt.set_log_level(matches.value_of(LOG_LEVEL).unwrap_or(""))?;
 
         // set boot filter, if provided
         if matches.is_present(BOOT_FILTER) {
             let boot_ids = matches.values_of(BOOT_FILTER).unwrap();
-            for id in boot_ids {
-                cli_opt.boot_filter.push(id.to_string());
-            }
+            cli_opt.set_boot_filter(boot_ids.map(String::from).collect());
         }
 
         // set (systemd) unit filter, if provided
         if matches.is_present(UNIT_FILTER) {
-            let units = matches.values_of(UNIT_FILTER).unwrap();
-            for unit in units {
-                // "raw" unit name, if .service provided by user
-                cli_opt.unit_filter.push(unit.to_string());
+            cli_opt.set_unit_filter(matches.values_of(UNIT_FILTER).unwrap());
+        }
+
+        // set message pattern filter, if provided
+        if matches.is_present(GREP_FILTER) {
+            cli_opt.set_grep(matches.value_of(GREP_FILTER).unwrap())?;
+        }
+
+        cli_opt.kernel_flag = matches.is_present(KERNEL_FLAG);
+
+        // set syslog facility filter, if provided
+        if matches.is_present(FACILITY_FILTER) {
+            for facility in matches.values_of(FACILITY_FILTER).unwrap() {
+                cli_opt.facility_filter.push(parse_facility(facility)?);
+            }
+        }
 
-                if unit.contains(".service") {
-                    continue;
+        // set transport filter, if provided
+        if matches.is_present(TRANSPORT_FILTER) {
+            for transport in matches.values_of(TRANSPORT_FILTER).unwrap() {
+                let transport = transport.to_lowercase();
+                if !TRANSPORT_NAMES.contains(&transport.as_str()) {
+                    bail!(
+                        "Invalid transport: {}, expected one of {}",
+                        transport,
+                        TRANSPORT_NAMES.join(", ")
+                    );
                 }
-                // add service variant of unit name
-                let unit_service = unit.to_string() + &".service";
-                cli_opt.unit_filter.push(unit_service.to_string());
+                cli_opt.transport_filter.push(transport);
             }
         }
 
-        if matches.is_present(KERNEL_FLAG) {
-            cli_opt.kernel_flag = true;
-        } else {
-            cli_opt.kernel_flag = false;
+        // set PID filter, if provided
+        if matches.is_present(PID_FILTER) {
+            for pid in matches.values_of(PID_FILTER).unwrap() {
+                cli_opt.pid_filter.push(pid.parse::<u32>()?);
+            }
+        }
+
+        // set host and machine filters, if provided
+        if matches.is_present(HOST_FILTER) {
+            for host in matches.values_of(HOST_FILTER).unwrap() {
+                cli_opt.host_filter.push(host.to_string());
+            }
+        }
+
+        if matches.is_present(MACHINE_FILTER) {
+            for machine in matches.values_of(MACHINE_FILTER).unwrap() {
+                cli_opt.machine_filter.push(machine.to_string());
+            }
+        }
+
+        // set UID filter, if provided. Either a single UID or a range FROM..TO
+        if matches.is_present(UID_FILTER) {
+            for uid in matches.values_of(UID_FILTER).unwrap() {
+                let range = match uid.split_once("..") {
+                    Some((from, to)) => {
+                        let from = if from.is_empty() {
+                            0
+                        } else {
+                            from.parse::<u32>()?
+                        };
+                        let to = if to.is_empty() {
+                            u32::MAX
+                        } else {
+                            to.parse::<u32>()?
+                        };
+                        if from > to {
+                            bail!("Invalid UID range: {}, start is after stop", uid);
+                        }
+                        (from, to)
+                    }
+                    None => {
+                        let uid = uid.parse::<u32>()?;
+                        (uid, uid)
+                    }
+                };
+                cli_opt.uid_filter.push(range);
+            }
         }
 
         if matches.is_present(NUM_OF_ENTRIES) {
@@ -174,53 +694,436 @@ impl CliOptions {
             cli_opt.num_of_entries = num;
         }
 
-        // Comment: Time operations below could maybe be done without the NaiveDateTime,
-        // but this felt alright and does the trick.
+        if matches.is_present(NUM_OF_LINES) {
+            let num = matches.value_of(NUM_OF_LINES).unwrap();
+            let num = num.parse::<u32>()?;
+            cli_opt.num_of_lines = num;
+        }
 
-        if matches.is_present(TIME_FROM) {
-            let input = matches.value_of(TIME_FROM).unwrap();
-            let time = NaiveTime::parse_from_str(input, "%H:%M:%S")?;
+        cli_opt.limit_overall = matches.is_present(LIMIT_OVERALL_FLAG);
 
-            // Recalculate to seconds from midnight
-            let from_midnight_s = time.hour() * 3600 + time.minute() * 60 + time.second();
+        cli_opt.reverse = matches.is_present(REVERSE_FLAG);
+        cli_opt.flat = matches.is_present(FLAT_FLAG);
 
-            cli_opt.start_time_filter = from_midnight_s as i64;
+        if matches.is_present(OUTPUT_MODE) {
+            cli_opt.output_mode = match matches.value_of(OUTPUT_MODE).unwrap() {
+                "table" => OutputMode::Table,
+                "json" => OutputMode::Json,
+                "csv" => OutputMode::Csv,
+                "tsv" => OutputMode::Tsv,
+                "short" => OutputMode::Short,
+                "short-iso" => OutputMode::ShortIso,
+                "short-precise" => OutputMode::ShortPrecise,
+                "short-monotonic" => OutputMode::ShortMonotonic,
+                "with-unit" => OutputMode::WithUnit,
+                "cat" => OutputMode::Cat,
+                "verbose" => OutputMode::Verbose,
+                other => bail!("Invalid output mode: {}", other),
+            };
         }
 
-        if matches.is_present(TIME_TO) {
-            let input = matches.value_of(TIME_TO).unwrap();
-            let time = NaiveTime::parse_from_str(input, "%H:%M:%S")?;
+        cli_opt.details = matches.is_present(DETAILS_FLAG);
 
-            // As above
-            let from_midnight_s = time.hour() * 3600 + time.minute() * 60 + time.second();
+        if matches.is_present(TRUNCATE_FLAG) {
+            cli_opt.line_mode = LineMode::Truncate;
+        } else if matches.is_present(WRAP_FLAG) {
+            cli_opt.line_mode = LineMode::Wrap;
+        }
 
-            cli_opt.stop_time_filter = from_midnight_s as i64;
+        // set hidden table columns, if provided
+        if matches.is_present(HIDE_COLUMNS) {
+            for column in matches.values_of(HIDE_COLUMNS).unwrap() {
+                cli_opt.hidden_columns.push(column.to_string());
+            }
         }
 
-        if matches.is_present(DATE_FROM) {
-            let input = matches.value_of(DATE_FROM).unwrap();
-            let date = NaiveDate::parse_from_str(input, "%Y:%m:%d")?;
+        // set format template, implies template output
+        if matches.is_present(FORMAT_TEMPLATE) {
+            cli_opt.template = Template::parse(matches.value_of(FORMAT_TEMPLATE).unwrap())?;
+            cli_opt.output_mode = OutputMode::Template;
+        }
 
-            // Construct a new 'NaiveDateTime' struct in order to get UTC timestamp from DateTimeStruct
-            // Note this is since 1970 and NOT UTC 1970
-            let td = NaiveDateTime::new(date, NaiveTime::from_hms(0, 0, 0));
-            // Construct a utc DateTime, and get the UTC timestamp
-            let dt_utc =
-                DateTime::<UTC>::from_utc(NaiveDateTime::from_timestamp(td.timestamp(), 0), UTC);
-            cli_opt.start_date_filter = dt_utc.timestamp() as i64;
+        // set output columns, computed columns or journal fields (upper case)
+        if matches.is_present(COLUMNS) {
+            for column in matches.values_of(COLUMNS).unwrap() {
+                if !valid_column(column) {
+                    bail!(
+                        "Invalid column: {}, expected a journal field name or one of {}",
+                        column,
+                        COMPUTED_COLUMNS.join(", ")
+                    );
+                }
+                cli_opt.columns.push(column.to_string());
+            }
+        } else {
+            cli_opt.columns = vec![
+                COLUMN_TIME.to_string(),
+                COLUMN_BOOT.to_string(),
+                COLUMN_SEQNUM.to_string(),
+                COLUMN_PRIORITY.to_string(),
+                COLUMN_UNIT.to_string(),
+                "MESSAGE".to_string(),
+            ];
+        }
+
+        if matches.is_present(SORT_KEY) {
+            cli_opt.sort_key = match matches.value_of(SORT_KEY).unwrap() {
+                "time" => Some(SortKey::Time),
+                "seqnum" => Some(SortKey::Seqnum),
+                "priority" => Some(SortKey::Priority),
+                other => bail!("Invalid sort key: {}", other),
+            };
+        }
+
+        if matches.is_present(TIME_FROM) {
+            cli_opt.set_time_from(matches.value_of(TIME_FROM).unwrap())?;
+        }
+
+        if matches.is_present(TIME_TO) {
+            cli_opt.set_time_to(matches.value_of(TIME_TO).unwrap())?;
+        }
+
+        if matches.is_present(DATE_FROM) {
+            cli_opt.set_date_from(matches.value_of(DATE_FROM).unwrap())?;
         }
 
         if matches.is_present(DATE_TO) {
-            let input = matches.value_of(DATE_TO).unwrap();
-            let date = NaiveDate::parse_from_str(input, "%Y:%m:%d")?;
+            cli_opt.set_date_to(matches.value_of(DATE_TO).unwrap())?;
+        }
+
+        if matches.is_present(TIMESTAMP_MODE) {
+            cli_opt.timestamp_mode = match matches.value_of(TIMESTAMP_MODE).unwrap() {
+                "realtime" => TimestampMode::Realtime,
+                "monotonic" => TimestampMode::Monotonic,
+                "delta" => TimestampMode::Delta,
+                other => bail!("Invalid timestamp mode: {}", other),
+            };
+        }
 
-            // As above
-            let td = NaiveDateTime::new(date, NaiveTime::from_hms(0, 0, 0));
-            let dt_utc =
-                DateTime::<UTC>::from_utc(NaiveDateTime::from_timestamp(td.timestamp(), 0), UTC);
-            cli_opt.stop_date_filter = dt_utc.timestamp() as i64;
+        cli_opt.precise = matches.is_present(PRECISE_FLAG);
+
+        if matches.is_present(BOOT_TIME) {
+            let input = matches.value_of(BOOT_TIME).unwrap();
+            let (from, to) = parse_boot_time_range(input)?;
+            cli_opt.boot_time_from = from;
+            cli_opt.boot_time_to = to;
+        }
+
+        if matches.is_present(CURSOR) {
+            cli_opt.cursor = Some(matches.value_of(CURSOR).unwrap().to_string());
+        }
+
+        if matches.is_present(AFTER_CURSOR) {
+            cli_opt.cursor = Some(matches.value_of(AFTER_CURSOR).unwrap().to_string());
+            cli_opt.after_cursor = true;
         }
 
+        cli_opt.show_cursor = matches.is_present(SHOW_CURSOR_FLAG);
+
+        if matches.is_present(CURSOR_FILE) {
+            let path = matches.value_of(CURSOR_FILE).unwrap();
+
+            // Start after the stored cursor if the file exists, otherwise start from the beginning
+            if Path::new(path).exists() {
+                let stored = fs::read_to_string(path)?;
+                let stored = stored.trim();
+                if !stored.is_empty() {
+                    cli_opt.cursor = Some(stored.to_string());
+                    cli_opt.after_cursor = true;
+                }
+            }
+            cli_opt.cursor_file = Some(path.to_string());
+        }
+
+        if matches.is_present(OUTPUT_FILE) {
+            cli_opt.output_file = Some(matches.value_of(OUTPUT_FILE).unwrap().to_string());
+        }
+
+        // Table layout is adjusted to terminal width, not used for output files
+        if cli_opt.output_file.is_none() {
+            cli_opt.terminal_width = terminal_width();
+        }
+
+        // Colors are used in auto mode if output is written to a terminal, unless disabled
+        // by NO_COLOR
+        cli_opt.color = match matches.value_of(COLOR_MODE).unwrap_or("auto") {
+            "always" => true,
+            "never" => false,
+            "auto" => {
+                let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                !no_color && cli_opt.output_file.is_none() && io::stdout().is_terminal()
+            }
+            other => bail!("Invalid color mode: {}", other),
+        };
+
+        // Output is shown in a pager if written to a terminal
+        cli_opt.interactive = matches.is_present(INTERACTIVE_FLAG);
+        cli_opt.pager = !matches.is_present(NO_PAGER_FLAG)
+            && !cli_opt.interactive
+            && cli_opt.output_file.is_none()
+            && io::stdout().is_terminal();
+
         return Ok(cli_opt);
     }
 }
+
+//------------------------------------------------------------------------------
+// Filter setters, used for the command line options and filter editing in the
+// interactive viewer. An empty input clears the filter.
+//------------------------------------------------------------------------------
+
+impl CliOptions {
+    // set log level range. A single level means that level and all levels with higher
+    // priority, as for journalctl
+    pub fn set_log_level(&mut self, input: &str) -> Result<(), failure::Error> {
+        let (mut from, mut to) = (0, 7);
+        let input = input.trim();
+        match input.split_once("..") {
+            Some((from_input, to_input)) => {
+                // An empty side leaves the range open in that direction
+                if !from_input.is_empty() {
+                    from = parse_priority(from_input)?;
+                }
+                if !to_input.is_empty() {
+                    to = parse_priority(to_input)?;
+                }
+                if from > to {
+                    bail!(
+                        "Invalid priority range: {}, start is lower priority then stop",
+                        input
+                    );
+                }
+            }
+            None if input.is_empty() => (),
+            None => to = parse_priority(input)?,
+        }
+        self.log_level_from = from;
+        self.log_level_to = to;
+        return Ok(());
+    }
+
+    pub fn set_boot_filter(&mut self, boot_ids: Vec<String>) {
+        self.boot_filter = boot_ids;
+    }
+
+    pub fn set_unit_filter<'a, I: Iterator<Item = &'a str>>(&mut self, units: I) {
+        self.unit_filter.clear();
+        for unit in units {
+            // "raw" unit name, if .service provided by user
+            self.unit_filter.push(unit.to_string());
+
+            if unit.contains(".service") {
+                continue;
+            }
+            // add service variant of unit name
+            let unit_service = unit.to_string() + ".service";
+            self.unit_filter.push(unit_service);
+        }
+    }
+
+    // set message pattern filter, see grep_regex
+    pub fn set_grep(&mut self, pattern: &str) -> Result<(), failure::Error> {
+        self.grep = if pattern.is_empty() {
+            None
+        } else {
+            Some(grep_regex(pattern)?)
+        };
+        return Ok(());
+    }
+
+    pub fn set_time_from(&mut self, input: &str) -> Result<(), failure::Error> {
+        self.start_time_filter = parse_time_of_day(input)?;
+        return Ok(());
+    }
+
+    pub fn set_time_to(&mut self, input: &str) -> Result<(), failure::Error> {
+        self.stop_time_filter = parse_time_of_day(input)?;
+        return Ok(());
+    }
+
+    pub fn set_date_from(&mut self, input: &str) -> Result<(), failure::Error> {
+        self.start_date_filter = parse_date(input)?;
+        return Ok(());
+    }
+
+    pub fn set_date_to(&mut self, input: &str) -> Result<(), failure::Error> {
+        self.stop_date_filter = parse_date(input)?;
+        return Ok(());
+    }
+}
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+// Check if name is a valid output column, a computed column or a journal field name
+// (upper case letters, digits and underscores)
+pub fn valid_column(name: &str) -> bool {
+    let is_field = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    return is_field || COMPUTED_COLUMNS.contains(&name);
+}
+
+// Get name of priority, N/A for entries without valid priority
+pub fn priority_name(priority: Option<u32>) -> &'static str {
+    match priority {
+        Some(level) if (level as usize) < PRIORITY_NAMES.len() => {
+            return PRIORITY_NAMES[level as usize];
+        }
+        _ => return "N/A",
+    }
+}
+
+// Get width of terminal, from COLUMNS if output is not a terminal
+fn terminal_width() -> Option<usize> {
+    if io::stdout().is_terminal() {
+        if let Ok((columns, _)) = terminal::size() {
+            return Some(columns as usize);
+        }
+    }
+    return env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok());
+}
+
+// Build regex for message pattern, case insensitive if the pattern is all lower case,
+// as for journalctl --grep
+pub fn grep_regex(pattern: &str) -> Result<Regex, failure::Error> {
+    let ignore_case = !pattern.chars().any(|c| c.is_uppercase());
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()?;
+    return Ok(regex);
+}
+
+// Parse time of day (HH:MM:SS) to seconds from midnight, 0 for empty input
+pub fn parse_time_of_day(input: &str) -> Result<i64, failure::Error> {
+    if input.is_empty() {
+        return Ok(0);
+    }
+    let time = NaiveTime::parse_from_str(input, "%H:%M:%S")?;
+
+    // Recalculate to seconds from midnight
+    let from_midnight_s = time.hour() * 3600 + time.minute() * 60 + time.second();
+    return Ok(from_midnight_s as i64);
+}
+
+// Parse date (YYYY:MM:DD) to UTC timestamp of midnight, 0 for empty input
+pub fn parse_date(input: &str) -> Result<i64, failure::Error> {
+    if input.is_empty() {
+        return Ok(0);
+    }
+    let date = NaiveDate::parse_from_str(input, "%Y:%m:%d")?;
+
+    // Comment: Time operations below could maybe be done without the NaiveDateTime,
+    // but this felt alright and does the trick.
+
+    // Construct a new 'NaiveDateTime' struct in order to get UTC timestamp from DateTimeStruct
+    // Note this is since 1970 and NOT UTC 1970
+    let td = NaiveDateTime::new(date, NaiveTime::from_hms(0, 0, 0));
+    // Construct a utc DateTime, and get the UTC timestamp
+    let dt_utc = DateTime::<UTC>::from_utc(NaiveDateTime::from_timestamp(td.timestamp(), 0), UTC);
+    return Ok(dt_utc.timestamp());
+}
+
+// Parse a priority as level number (0-7) or name (emerg..debug)
+fn parse_priority(input: &str) -> Result<u32, failure::Error> {
+    let input = input.trim();
+    if let Ok(level) = input.parse::<u32>() {
+        if level > 7 {
+            bail!("Invalid priority: {}, expected level 0-7", level);
+        }
+        return Ok(level);
+    }
+
+    // Aliases accepted by syslog and journalctl
+    let name = match input.to_lowercase().as_str() {
+        "panic" => "emerg".to_string(),
+        "error" => "err".to_string(),
+        "warn" => "warning".to_string(),
+        other => other.to_string(),
+    };
+
+    match PRIORITY_NAMES.iter().position(|n| *n == name) {
+        Some(level) => return Ok(level as u32),
+        None => bail!(
+            "Invalid priority: {}, expected level 0-7 or one of {}",
+            input,
+            PRIORITY_NAMES.join(", ")
+        ),
+    }
+}
+
+// Parse a syslog facility as number (0-23) or name (kern, daemon, local0 etc.)
+fn parse_facility(input: &str) -> Result<u32, failure::Error> {
+    if let Ok(facility) = input.parse::<u32>() {
+        if facility as usize >= FACILITY_NAMES.len() {
+            bail!("Invalid facility: {}, expected 0-23", facility);
+        }
+        return Ok(facility);
+    }
+
+    match FACILITY_NAMES
+        .iter()
+        .position(|n| *n == input.to_lowercase())
+    {
+        Some(facility) => return Ok(facility as u32),
+        None => bail!(
+            "Invalid facility: {}, expected 0-23 or one of {}",
+            input,
+            FACILITY_NAMES.join(", ")
+        ),
+    }
+}
+
+// Parse a monotonic range, e.g. "0s..30s", "..500ms" or "2m..", into microseconds
+fn parse_boot_time_range(input: &str) -> Result<(Option<u64>, Option<u64>), failure::Error> {
+    let bounds: Vec<&str> = input.splitn(2, "..").collect();
+    if bounds.len() != 2 {
+        bail!(
+            "Invalid boot time range: {}, expected <start>..<stop>",
+            input
+        );
+    }
+
+    let from = match bounds[0].trim() {
+        "" => None,
+        b => Some(parse_duration_us(b)?),
+    };
+    let to = match bounds[1].trim() {
+        "" => None,
+        b => Some(parse_duration_us(b)?),
+    };
+
+    if let (Some(f), Some(t)) = (from, to) {
+        if f > t {
+            bail!("Invalid boot time range: {}, start is after stop", input);
+        }
+    }
+
+    return Ok((from, to));
+}
+
+// Parse a duration with an optional unit suffix (us, ms, s, m, h, d) into microseconds,
+// a value without unit is treated as seconds
+fn parse_duration_us(input: &str) -> Result<u64, failure::Error> {
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (value, unit) = input.split_at(split);
+
+    let value = value.parse::<f64>()?;
+    let factor = match unit {
+        "us" => 1.0,
+        "ms" => 1_000.0,
+        "" | "s" => 1_000_000.0,
+        "m" | "min" => 60_000_000.0,
+        "h" => 3_600_000_000.0,
+        "d" => 86_400_000_000.0,
+        _ => bail!("Invalid time unit: {} in {}", unit, input),
+    };
+
+    return Ok((value * factor) as u64);
+}
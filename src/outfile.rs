@@ -0,0 +1,104 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: outfile.rs
+// File Description: Output file, written atomically and optionally compressed
+// License: MIT
+//------------------------------------------------------------------------------
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+
+//------------------------------------------------------------------------------
+// Output file type
+//------------------------------------------------------------------------------
+
+enum Encoder {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+// Output file, written to a temporary file in the same directory which replaces the
+// target file when finished, so the target is never left partially written.
+// The output is gzip compressed if the file name ends with '.gz'.
+pub struct OutputFile {
+    path: PathBuf,
+    tmp_path: PathBuf,
+    encoder: Option<Encoder>,
+}
+
+impl OutputFile {
+    // Create the temporary file for writing output to path
+    pub fn create(path: &str) -> io::Result<OutputFile> {
+        let path = PathBuf::from(path);
+        let file_name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => {
+                let msg = format!("Invalid output file: {}", path.display());
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+            }
+        };
+        let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()));
+
+        let file = match File::create(&tmp_path) {
+            Ok(file) => BufWriter::new(file),
+            Err(err) => {
+                let msg = format!("Unable to create output file {}: {}", path.display(), err);
+                return Err(io::Error::new(err.kind(), msg));
+            }
+        };
+        let encoder = if file_name.ends_with(".gz") {
+            Encoder::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            Encoder::Plain(file)
+        };
+
+        return Ok(OutputFile {
+            path,
+            tmp_path,
+            encoder: Some(encoder),
+        });
+    }
+
+    // Finish compression, flush output to disk and move the temporary file in place
+    pub fn finish(mut self) -> io::Result<()> {
+        let writer = match self.encoder.take() {
+            Some(Encoder::Plain(writer)) => writer,
+            Some(Encoder::Gzip(encoder)) => encoder.finish()?,
+            None => return Ok(()),
+        };
+        let file = writer.into_inner().map_err(|err| err.into_error())?;
+        file.sync_all()?;
+        fs::rename(&self.tmp_path, &self.path)?;
+        return Ok(());
+    }
+
+    fn writer(&mut self) -> io::Result<&mut dyn Write> {
+        match self.encoder.as_mut() {
+            Some(Encoder::Plain(writer)) => return Ok(writer),
+            Some(Encoder::Gzip(encoder)) => return Ok(encoder),
+            None => return Err(io::Error::other("Output file is already finished")),
+        }
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        return self.writer()?.write(buf);
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.writer()?.flush();
+    }
+}
+
+// Remove the temporary file if output was not finished, e.g. due to a write error.
+// Once finished the temporary file is already moved and nothing is removed
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.tmp_path);
+    }
+}
@@ -0,0 +1,430 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: output.rs
+// File Description: Output formats for log entries, other then the default table
+// License: MIT
+//------------------------------------------------------------------------------
+
+use chrono::format::{Item, StrftimeItems};
+use serde_json::{Map, Value};
+use std::io::{self, Write};
+
+use crate::color::Colors;
+use crate::errno;
+use crate::opt;
+use crate::parser::{self, LogEntry};
+
+//------------------------------------------------------------------------------
+// Format template types
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Default)]
+pub struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone)]
+enum TemplatePart {
+    Text(String),
+    Field(TemplateField),
+}
+
+#[derive(Debug, Clone)]
+pub struct TemplateField {
+    // Journal field or computed column name
+    pub name: String,
+    // Value format, strftime format for time or name/num for priority
+    pub format: Option<String>,
+    align: char,
+    width: usize,
+    max_width: Option<usize>,
+}
+
+//------------------------------------------------------------------------------
+// Template associated functions and methods
+//------------------------------------------------------------------------------
+
+impl Template {
+    // Parse a format template, e.g. "{time:%H:%M:%S} {unit:<20} {MESSAGE}".
+    // Literal braces are written as "{{" and "}}".
+    pub fn parse(input: &str) -> Result<Template, failure::Error> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => placeholder.push(ch),
+                            None => bail!("Unterminated field in format template: {}", input),
+                        }
+                    }
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(text.clone()));
+                        text.clear();
+                    }
+                    parts.push(TemplatePart::Field(TemplateField::parse(&placeholder)?));
+                }
+                '}' => bail!("Unmatched '}}' in format template: {}", input),
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+
+        return Ok(Template { parts });
+    }
+
+    // Render template, with field values from the value function
+    pub fn render<F: Fn(&TemplateField) -> String>(&self, value: F) -> String {
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Text(text) => line.push_str(text),
+                TemplatePart::Field(field) => line.push_str(&field.pad(value(field))),
+            }
+        }
+        return line;
+    }
+}
+
+impl TemplateField {
+    // Parse a template field "name[:spec]"
+    fn parse(placeholder: &str) -> Result<TemplateField, failure::Error> {
+        let (name, spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec)),
+            None => (placeholder.trim(), None),
+        };
+        if !opt::valid_column(name) {
+            bail!(
+                "Invalid field in format template: {}, expected a journal field name or one of {}",
+                name,
+                opt::COMPUTED_COLUMNS.join(", ")
+            );
+        }
+
+        let mut field = TemplateField {
+            name: name.to_string(),
+            format: None,
+            align: '<',
+            width: 0,
+            max_width: None,
+        };
+
+        let spec = match spec {
+            Some(s) => s,
+            None => return Ok(field),
+        };
+
+        // Value format specifiers
+        if name == opt::COLUMN_TIME && spec.contains('%') {
+            // Invalid time formats would fail when entries are formatted
+            if StrftimeItems::new(spec).any(|item| item == Item::Error) {
+                bail!("Invalid time format '{}' for field {}", spec, name);
+            }
+            field.format = Some(spec.to_string());
+            return Ok(field);
+        }
+        if name == opt::COLUMN_PRIORITY && (spec == "name" || spec == "num") {
+            field.format = Some(spec.to_string());
+            return Ok(field);
+        }
+
+        // Alignment specifier [<>^][width][.max]
+        let mut rest = spec;
+        if let Some(align) = rest.chars().next().filter(|c| "<>^".contains(*c)) {
+            field.align = align;
+            rest = &rest[1..];
+        }
+        let (width, max) = match rest.split_once('.') {
+            Some((width, max)) => (width, Some(max)),
+            None => (rest, None),
+        };
+        let invalid = || format_err!("Invalid format specifier '{}' for field {}", spec, name);
+        if !width.is_empty() {
+            field.width = width.parse::<usize>().map_err(|_| invalid())?;
+        }
+        if let Some(max) = max {
+            field.max_width = Some(max.parse::<usize>().map_err(|_| invalid())?);
+        }
+
+        return Ok(field);
+    }
+
+    // Truncate and pad value according to alignment specifier
+    fn pad(&self, value: String) -> String {
+        let value = match self.max_width {
+            Some(max) if value.chars().count() > max => value.chars().take(max).collect(),
+            _ => value,
+        };
+        match self.align {
+            '>' => return format!("{:>width$}", value, width = self.width),
+            '^' => return format!("{:^width$}", value, width = self.width),
+            _ => return format!("{:<width$}", value, width = self.width),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Json output
+//------------------------------------------------------------------------------
+
+// Print entry as a single line json object with all fields of the entry,
+// same shape as 'journalctl -o json'
+pub fn print_json(out: &mut dyn Write, entry: &LogEntry) -> io::Result<()> {
+    let value = serde_json::to_value(entry)?;
+
+    // Remove fields not present in the original entry
+    let fields: Map<String, Value> = match value {
+        Value::Object(fields) => fields.into_iter().filter(|(_, v)| !v.is_null()).collect(),
+        _ => {
+            let msg = "Entry is not a json object";
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+    };
+
+    serde_json::to_writer(&mut *out, &fields)?;
+    writeln!(out)?;
+    return Ok(());
+}
+
+//------------------------------------------------------------------------------
+// Delimited (csv/tsv) output
+//------------------------------------------------------------------------------
+
+// Print values as a delimited row. For csv (',') values are quoted when needed,
+// for tsv ('\t') tabs, newlines and backslashes are escaped.
+pub fn print_delimited(out: &mut dyn Write, values: &[String], delimiter: char) -> io::Result<()> {
+    let row: Vec<String> = values
+        .iter()
+        .map(|v| {
+            if delimiter == '\t' {
+                return escape_tsv(v);
+            }
+            return quote_csv(v, delimiter);
+        })
+        .collect();
+    writeln!(out, "{}", row.join(&delimiter.to_string()))?;
+    return Ok(());
+}
+
+// Quote csv value if it contains delimiter, quotes or line breaks (RFC 4180)
+fn quote_csv(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        return format!("\"{}\"", value.replace('"', "\"\""));
+    }
+    return value.to_string();
+}
+
+// Escape characters that can't be part of a tsv value
+fn escape_tsv(value: &str) -> String {
+    return value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+}
+
+//------------------------------------------------------------------------------
+// journalctl text output
+//------------------------------------------------------------------------------
+
+// Print entry in one of the journalctl short formats (short, short-iso, short-precise,
+// short-monotonic or with-unit), "<time> <host> <identifier>[<pid>]: <message>".
+// The message is colored by priority
+pub fn print_short(
+    out: &mut dyn Write,
+    entry: &LogEntry,
+    mode: opt::OutputMode,
+    colors: &Colors,
+) -> io::Result<()> {
+    let timestamp = match mode {
+        opt::OutputMode::ShortMonotonic => {
+            let monotonic = entry
+                .__MONOTONIC_TIMESTAMP
+                .as_ref()
+                .and_then(|t| t.parse::<u64>().ok());
+            match monotonic {
+                Some(ts) => format!("[{:>5}.{:06}]", ts / 1000000, ts % 1000000),
+                None => "[N/A]".to_string(),
+            }
+        }
+        opt::OutputMode::ShortIso => format_realtime(entry, "%Y-%m-%dT%H:%M:%S%:z"),
+        opt::OutputMode::ShortPrecise => format_realtime(entry, "%b %d %H:%M:%S%.6f"),
+        opt::OutputMode::WithUnit => format_realtime(entry, "%a %Y-%m-%d %H:%M:%S UTC"),
+        _ => format_realtime(entry, "%b %d %H:%M:%S"),
+    };
+
+    // with-unit uses the unit instead of the syslog identifier
+    let identifier = if mode == opt::OutputMode::WithUnit {
+        entry
+            ._SYSTEMD_UNIT
+            .clone()
+            .or_else(|| entry.unit_name().cloned())
+    } else {
+        entry
+            .SYSLOG_IDENTIFIER
+            .clone()
+            .or_else(|| entry.field("_COMM"))
+    };
+    let pid = match entry._PID {
+        Some(ref pid) => format!("[{}]", pid),
+        None => String::new(),
+    };
+
+    let prefix = format!(
+        "{} {} {}{}: ",
+        timestamp,
+        entry._HOSTNAME.as_deref().unwrap_or("localhost"),
+        identifier.as_deref().unwrap_or("unknown"),
+        pid
+    );
+    let priority = entry.PRIORITY.as_ref().and_then(|p| p.parse::<u32>().ok());
+    return print_continued(
+        out,
+        &prefix,
+        entry.MESSAGE.as_deref().unwrap_or(""),
+        |line| colors.priority(line, priority),
+    );
+}
+
+// Print entry message only, as journalctl cat format
+pub fn print_cat(out: &mut dyn Write, entry: &LogEntry) -> io::Result<()> {
+    writeln!(out, "{}", entry.MESSAGE.as_deref().unwrap_or(""))?;
+    return Ok(());
+}
+
+// Print entry with all fields, as journalctl verbose format
+pub fn print_verbose(out: &mut dyn Write, entry: &LogEntry) -> io::Result<()> {
+    writeln!(
+        out,
+        "{} [{}]",
+        format_realtime(entry, "%a %Y-%m-%d %H:%M:%S%.6f UTC"),
+        entry.__CURSOR.as_deref().unwrap_or("N/A")
+    )?;
+    return print_fields(out, entry, "    ");
+}
+
+// Print all fields of entry as "<indent>NAME=value", except the address fields
+// (cursor and timestamps). ERRNO is shown with symbolic name and description.
+pub fn print_fields(out: &mut dyn Write, entry: &LogEntry, indent: &str) -> io::Result<()> {
+    let fields = match serde_json::to_value(entry) {
+        Ok(Value::Object(fields)) => fields,
+        _ => return Ok(()),
+    };
+    for (name, value) in fields {
+        // Address fields are shown in the header line
+        if name.starts_with("__") {
+            continue;
+        }
+        let mut value = match value {
+            Value::Null => continue,
+            Value::String(s) => s,
+            other => other.to_string(),
+        };
+        if name == "ERRNO" {
+            if let Some(description) = errno::errno_description(&value) {
+                value = format!("{} ({})", value, description);
+            }
+        }
+        print_continued(out, &format!("{}{}=", indent, name), &value, |line| {
+            line.to_string()
+        })?;
+    }
+    return Ok(());
+}
+
+// Cut text to max width characters, ending with an ellipsis if cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    return cut;
+}
+
+// Wrap text into lines of max width characters, at whitespace if possible.
+// Indentation of text is kept on all lines
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let trimmed = text.trim_start_matches(' ');
+    let indent = &text[..text.len() - trimmed.len()];
+    let width = width.saturating_sub(indent.len()).max(1);
+    let mut lines = Vec::new();
+    let mut line = indent.to_string();
+    let mut line_width = 0;
+
+    for word in trimmed.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+
+        // Start a new line if the word doesn't fit on the current line
+        if line_width > 0 && line_width + 1 + word.len() > width {
+            lines.push(std::mem::replace(&mut line, indent.to_string()));
+            line_width = 0;
+        }
+        if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+
+        // Words longer than a line are split
+        while line_width + word.len() > width {
+            let rest = word.split_off(width - line_width);
+            line.extend(word);
+            lines.push(std::mem::replace(&mut line, indent.to_string()));
+            line_width = 0;
+            word = rest;
+        }
+        line_width += word.len();
+        line.extend(word);
+    }
+    lines.push(line);
+    return lines;
+}
+
+// Format realtime timestamp of entry
+fn format_realtime(entry: &LogEntry, time_format: &str) -> String {
+    let realtime = entry
+        .__REALTIME_TIMESTAMP
+        .as_ref()
+        .and_then(|t| t.parse::<i64>().ok());
+    match realtime {
+        Some(ts) => return parser::format_time(ts, time_format),
+        None => return "N/A".to_string(),
+    }
+}
+
+// Print prefix and a (possibly multi-line) text, continuation lines are indented
+// to the width of the prefix. Each line of text is passed through paint, for coloring
+fn print_continued<F: Fn(&str) -> String>(
+    out: &mut dyn Write,
+    prefix: &str,
+    text: &str,
+    paint: F,
+) -> io::Result<()> {
+    let indent = " ".repeat(prefix.chars().count());
+    for (index, line) in text.lines().enumerate() {
+        if index == 0 {
+            writeln!(out, "{}{}", prefix, paint(line))?;
+        } else {
+            writeln!(out, "{}{}", indent, paint(line))?;
+        }
+    }
+    if text.is_empty() {
+        writeln!(out, "{}", prefix)?;
+    }
+    return Ok(());
+}
@@ -0,0 +1,67 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: pager.rs
+// File Description: Pager process for terminal output
+// License: MIT
+//------------------------------------------------------------------------------
+
+use std::env;
+use std::io::{self, BufWriter, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+//------------------------------------------------------------------------------
+// Pager type
+//------------------------------------------------------------------------------
+
+// Default pager, -R keeps colors, -F quits if output fits on one screen,
+// -S chops long lines and -X keeps output on screen after quit
+const DEFAULT_PAGER: &str = "less -FRSX";
+
+// Running pager, output is written to the stdin of the pager process
+pub struct Pager {
+    child: Child,
+    input: Option<BufWriter<ChildStdin>>,
+}
+
+impl Pager {
+    // Start pager from $PAGER, or 'less -FRSX' if not set. Returns None if the pager is
+    // disabled (PAGER set to empty or 'cat') or could not be started
+    pub fn start() -> Option<Pager> {
+        let command = env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+        let mut args = command.split_whitespace();
+        let program = match args.next() {
+            Some("cat") | None => return None,
+            Some(p) => p,
+        };
+
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+        {
+            Ok(c) => c,
+            Err(err) => {
+                eprintln!("Unable to start pager '{}': {}", command, err);
+                return None;
+            }
+        };
+
+        let input = child.stdin.take().map(BufWriter::new);
+        return Some(Pager { child, input });
+    }
+
+    // Writer for pager input
+    pub fn input(&mut self) -> &mut dyn Write {
+        return self.input.as_mut().unwrap();
+    }
+
+    // Close pager input and wait for the user to quit the pager
+    pub fn wait(mut self) -> io::Result<()> {
+        let flushed = match self.input.take() {
+            Some(mut input) => input.flush(),
+            None => Ok(()),
+        };
+        self.child.wait()?;
+        return flushed;
+    }
+}
@@ -4,16 +4,32 @@
 // File Description: The systemd journal logfile parser functionality
 // License: MIT
 //------------------------------------------------------------------------------
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
 
 use chrono::prelude::*;
 use chrono::UTC;
 
 use serde::{Deserialize, Serialize};
 
+use crate::color;
+use crate::diff;
+use crate::digest;
+use crate::histogram;
+use crate::lifecycle;
 use crate::opt;
+use crate::output;
+use crate::stats;
+
+//------------------------------------------------------------------------------
+// Section header separator lines, banner lines for host and overall headers
+//------------------------------------------------------------------------------
+
+const SECTION_LINE: &str =
+    "- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
+const BANNER_LINE: &str =
+    "===============================================================================================";
 
 //------------------------------------------------------------------------------
 // systemd journal log entry struct type
@@ -21,42 +37,120 @@ use crate::opt;
 
 #[allow(non_snake_case)]
 #[derive(Debug, Serialize, Deserialize)]
-struct LogEntry {
-    __CURSOR: Option<String>,
-    __REALTIME_TIMESTAMP: Option<String>,
-    __MONOTONIC_TIMESTAMP: Option<String>,
-    _BOOT_ID: Option<String>,
-    _TRANSPORT: Option<String>,
-    SYSLOG_FACILITY: Option<String>,
-    _UID: Option<String>,
-    _GID: Option<String>,
-    _MACHINE_ID: Option<String>,
-    SYSLOG_IDENTIFIER: Option<String>,
-    _PID: Option<String>,
-    _CMDLINE: Option<String>,
-    _SYSTEMD_CGROUP: Option<String>,
-    _SYSTEMD_UNIT: Option<String>,
-    MESSAGE: Option<String>,
-    _HOSTNAME: Option<String>,
-    PRIORITY: Option<String>,
-    CODE_FILE: Option<String>,
-    CODE_LINE: Option<String>,
-    CODE_FUNCTION: Option<String>,
-    ERRNO: Option<String>,
-    UNIT: Option<String>,
+pub struct LogEntry {
+    pub __CURSOR: Option<String>,
+    pub __REALTIME_TIMESTAMP: Option<String>,
+    pub __MONOTONIC_TIMESTAMP: Option<String>,
+    pub _BOOT_ID: Option<String>,
+    pub _TRANSPORT: Option<String>,
+    pub SYSLOG_FACILITY: Option<String>,
+    pub _UID: Option<String>,
+    pub _GID: Option<String>,
+    pub _MACHINE_ID: Option<String>,
+    pub SYSLOG_IDENTIFIER: Option<String>,
+    pub _PID: Option<String>,
+    pub _CMDLINE: Option<String>,
+    pub _SYSTEMD_CGROUP: Option<String>,
+    pub _SYSTEMD_UNIT: Option<String>,
+    pub MESSAGE: Option<String>,
+    pub _HOSTNAME: Option<String>,
+    pub PRIORITY: Option<String>,
+    pub CODE_FILE: Option<String>,
+    pub CODE_LINE: Option<String>,
+    pub CODE_FUNCTION: Option<String>,
+    pub ERRNO: Option<String>,
+    pub UNIT: Option<String>,
+    // All other fields of the entry, kept for output of complete entries
+    #[serde(flatten)]
+    pub other_fields: BTreeMap<String, serde_json::Value>,
     // DOC:
     // https://www.freedesktop.org/software/systemd/man/systemd.journal-fields.html
 }
 
+//------------------------------------------------------------------------------
+// LogEntry associated methods
+//------------------------------------------------------------------------------
+
+impl LogEntry {
+    // Get unit name of entry, from UNIT, _SYSTEMD_UNIT or SYSLOG_IDENTIFIER in that order
+    pub fn unit_name(&self) -> Option<&String> {
+        return self
+            .UNIT
+            .as_ref()
+            .or(self._SYSTEMD_UNIT.as_ref())
+            .or(self.SYSLOG_IDENTIFIER.as_ref());
+    }
+
+    // Get value of a journal field by name, fields with non string values
+    // (e.g. binary data) are returned as json
+    pub fn field(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "__CURSOR" => &self.__CURSOR,
+            "__REALTIME_TIMESTAMP" => &self.__REALTIME_TIMESTAMP,
+            "__MONOTONIC_TIMESTAMP" => &self.__MONOTONIC_TIMESTAMP,
+            "_BOOT_ID" => &self._BOOT_ID,
+            "_TRANSPORT" => &self._TRANSPORT,
+            "SYSLOG_FACILITY" => &self.SYSLOG_FACILITY,
+            "_UID" => &self._UID,
+            "_GID" => &self._GID,
+            "_MACHINE_ID" => &self._MACHINE_ID,
+            "SYSLOG_IDENTIFIER" => &self.SYSLOG_IDENTIFIER,
+            "_PID" => &self._PID,
+            "_CMDLINE" => &self._CMDLINE,
+            "_SYSTEMD_CGROUP" => &self._SYSTEMD_CGROUP,
+            "_SYSTEMD_UNIT" => &self._SYSTEMD_UNIT,
+            "MESSAGE" => &self.MESSAGE,
+            "_HOSTNAME" => &self._HOSTNAME,
+            "PRIORITY" => &self.PRIORITY,
+            "CODE_FILE" => &self.CODE_FILE,
+            "CODE_LINE" => &self.CODE_LINE,
+            "CODE_FUNCTION" => &self.CODE_FUNCTION,
+            "ERRNO" => &self.ERRNO,
+            "UNIT" => &self.UNIT,
+            _ => {
+                return self.other_fields.get(name).map(|v| match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+            }
+        };
+        return value.clone();
+    }
+}
+
 //------------------------------------------------------------------------------
 // Formated log entry type
 //------------------------------------------------------------------------------
-struct FormattedLogEntry {
-    sequence_number: String,
-    timestamp: String,
-    loglevel: String,
-    unit_name: String,
-    message: String,
+#[derive(Debug, Clone)]
+pub struct FormattedLogEntry {
+    // Index of entry in log_entries
+    pub index: usize,
+    pub cursor: String,
+    // Raw values used for sorting
+    pub seqnum: Option<u64>,
+    pub realtime: i64,
+    pub priority: Option<u32>,
+    pub sequence_number: String,
+    pub timestamp: String,
+    pub loglevel: String,
+    pub unit_name: String,
+    pub unit: String,
+    pub pid: String,
+    pub message: String,
+}
+
+//------------------------------------------------------------------------------
+// Table layout type
+//------------------------------------------------------------------------------
+
+// Column widths of table output, None for hidden columns
+struct TableLayout {
+    seq: Option<usize>,
+    time: Option<usize>,
+    level: Option<usize>,
+    unit: Option<usize>,
+    // Show PID after unit name
+    pid: bool,
 }
 
 //------------------------------------------------------------------------------
@@ -66,9 +160,39 @@ struct FormattedLogEntry {
 pub struct ParsedLogfile {
     filename: String,
     log_entries: Vec<LogEntry>,
-    total_entries: usize,
     parse_opt: opt::CliOptions,
     boot_ids: Vec<String>,
+    hosts: Vec<HostInfo>,
+    boots: HashMap<String, BootInfo>,
+    colors: color::Colors,
+    // Number of lines that could not be read or parsed
+    malformed_lines: usize,
+    // Number of entries read after each logfile, to get the logfile of an entry
+    file_ends: Vec<usize>,
+}
+
+//------------------------------------------------------------------------------
+// Boot information struct type
+//------------------------------------------------------------------------------
+
+struct BootInfo {
+    // Index of host in hosts list
+    host: usize,
+    // First and last realtime timestamp of boot, microseconds since epoch
+    first_timestamp: Option<i64>,
+    last_timestamp: Option<i64>,
+    entries: usize,
+    kernel_version: Option<String>,
+}
+
+//------------------------------------------------------------------------------
+// Host (hostname and machine ID) struct type
+//------------------------------------------------------------------------------
+
+#[derive(PartialEq)]
+struct HostInfo {
+    hostname: String,
+    machine_id: String,
 }
 
 //------------------------------------------------------------------------------
@@ -81,42 +205,47 @@ impl ParsedLogfile {
         //
         let mut entries: Vec<LogEntry> = Vec::new();
         let mut ids: Vec<String> = Vec::new();
+        let mut hosts: Vec<HostInfo> = Vec::new();
+        let mut boots: HashMap<String, BootInfo> = HashMap::new();
+        let mut malformed_lines = 0;
+        let mut file_ends: Vec<usize> = Vec::new();
 
         #[allow(non_snake_case)]
         let NOT_AVAILABLE = String::from("N/A");
 
-        // open and read file json formatted systemd journal file
-        let file = File::open(parse_options.logfile_path())?;
-        let buf_reader = BufReader::new(file);
+        // open and read json formatted systemd journal file(s), in order
+        for path in parse_options.logfile_paths() {
+            let file = File::open(path)?;
+            let buf_reader = BufReader::new(file);
 
-        for line in buf_reader.lines() {
-            if line.is_err() {
-                eprintln!("Failed to read line, ignoring");
-                continue;
-            }
+            for line in buf_reader.lines() {
+                if line.is_err() {
+                    eprintln!("Failed to read line, ignoring");
+                    malformed_lines += 1;
+                    continue;
+                }
 
-            // get result as str and convert from json to LogEntry
-            let line_string = line.unwrap();
-            let line_str = line_string.as_str();
-            let entry_result = serde_json::from_str(line_str);
-            if entry_result.is_err() {
-                eprintln!(
-                    "Illformated line: {:?} - Ignoring entry!",
-                    entry_result.err().unwrap()
-                );
-                continue;
+                // get result as str and convert from json to LogEntry
+                let line_string = line.unwrap();
+                let line_str = line_string.as_str();
+                let entry_result = serde_json::from_str(line_str);
+                if entry_result.is_err() {
+                    eprintln!(
+                        "Illformated line: {:?} - Ignoring entry!",
+                        entry_result.err().unwrap()
+                    );
+                    malformed_lines += 1;
+                    continue;
+                }
+                let entry: LogEntry = entry_result.unwrap();
+                entries.push(entry);
             }
-            let entry: LogEntry = entry_result.unwrap();
-            entries.push(entry);
+            file_ends.push(entries.len());
         }
 
         let number = entries.len();
-
-        match entries[0]._BOOT_ID {
-            Some(ref id) => {
-                ids.push(id.clone());
-            }
-            _ => eprintln!("Unable to get boot ID"),
+        if number == 0 {
+            bail!("No valid log entries in logfile");
         }
 
         // Get all boot IDs from entry list
@@ -132,13 +261,67 @@ impl ParsedLogfile {
             if !ids.contains(&boot_id) {
                 ids.push(boot_id.clone());
             }
+
+            // Get host of entry, and map boot to host on first entry of boot
+            let host = HostInfo {
+                hostname: e._HOSTNAME.clone().unwrap_or_else(|| NOT_AVAILABLE.clone()),
+                machine_id: e
+                    ._MACHINE_ID
+                    .clone()
+                    .unwrap_or_else(|| NOT_AVAILABLE.clone()),
+            };
+            let host_index = match hosts.iter().position(|h| *h == host) {
+                Some(index) => index,
+                None => {
+                    hosts.push(host);
+                    hosts.len() - 1
+                }
+            };
+            let boot = boots.entry(boot_id).or_insert(BootInfo {
+                host: host_index,
+                first_timestamp: None,
+                last_timestamp: None,
+                entries: 0,
+                kernel_version: None,
+            });
+
+            // Update boot time range and entry count
+            boot.entries += 1;
+            let timestamp = e
+                .__REALTIME_TIMESTAMP
+                .as_ref()
+                .and_then(|t| t.parse::<i64>().ok());
+            if let Some(ts) = timestamp {
+                if boot.first_timestamp.is_none_or(|first| ts < first) {
+                    boot.first_timestamp = Some(ts);
+                }
+                if boot.last_timestamp.is_none_or(|last| ts > last) {
+                    boot.last_timestamp = Some(ts);
+                }
+            }
+
+            // Get kernel version from the kernel banner, "Linux version <version> ..."
+            if boot.kernel_version.is_none() && e._TRANSPORT.as_deref() == Some("kernel") {
+                if let Some(msg) = e.MESSAGE.as_ref() {
+                    if let Some(version) = msg.strip_prefix("Linux version ") {
+                        boot.kernel_version = version.split_whitespace().next().map(String::from);
+                    }
+                }
+            }
         }
+
+        // Order boots chronologically by first entry, boots without timestamps last
+        ids.sort_by_key(|id| boots[id].first_timestamp.unwrap_or(i64::MAX));
         return Ok(Self {
-            filename: parse_options.logfile_name().clone(),
+            filename: parse_options.logfile_names().join("', '"),
             log_entries: entries,
-            total_entries: number,
             parse_opt: parse_options.clone(),
             boot_ids: ids,
+            hosts,
+            boots,
+            colors: color::Colors::new(parse_options.color()),
+            malformed_lines,
+            file_ends,
         });
     }
 }
@@ -149,18 +332,51 @@ impl ParsedLogfile {
 
 impl ParsedLogfile {
     // Public function to print the log entries
-    pub fn print(self) {
+    pub fn print(self, out: &mut dyn Write) -> io::Result<()> {
+        let table_output = self.parse_opt.output_mode() == opt::OutputMode::Table;
+
         // Just for separation of the output in terminal
-        println!(" ");
+        if table_output {
+            writeln!(out, " ")?;
+        }
 
         // Print boot IDs list if flag is set
         if self.parse_opt.list_boots() {
-            self.print_boot_list();
-            return;
+            return self.print_boot_list(out);
+        }
+
+        // Print hosts list if flag is set
+        if self.parse_opt.list_hosts() {
+            return self.print_host_list(out);
+        }
+
+        // Print statistics instead of entries if flag is set
+        if self.parse_opt.stats() {
+            return self.print_stats(out);
+        }
+
+        // Print histogram instead of entries if flag is set
+        if self.parse_opt.histogram() {
+            return self.print_histogram(out);
+        }
+
+        // Print message digest instead of entries if flag is set
+        if self.parse_opt.digest() {
+            return self.print_digest(out);
+        }
+
+        // Print differences of two boots or logfiles instead of entries if flag is set
+        if self.parse_opt.diff() {
+            return self.print_diff(out);
+        }
+
+        // Print unit lifecycles instead of entries if flag is set
+        if self.parse_opt.units_report() {
+            return self.print_units_report(out);
         }
 
         //Sort and format Entries
-        let format_entries_result = self.format_entry_list();
+        let format_entries_result = self.ordered_entries();
 
         // Check result
         if format_entries_result.is_err() {
@@ -168,37 +384,419 @@ impl ParsedLogfile {
                 "Error accord while formatting log entries [ {:?} ], unable to display logs",
                 format_entries_result.err().unwrap()
             );
-            return;
+            return Ok(());
         }
 
         let formatted_entries = format_entries_result.unwrap();
-        for (boot, entries) in formatted_entries.iter() {
-            // print current boot 'header'
-            self.print_boot(&boot, self.total_entries);
-            self.print_column_header();
-
-            // Check if entry limit filter is used:
-            let mut e_counter: u32 = 1;
-            let e_nr_limit = self.parse_opt.num_of_entries();
-            for entry in entries {
-                if e_nr_limit == 0 {
-                    self.print_entry(entry);
-                } else if e_counter <= e_nr_limit && e_nr_limit > 0 {
-                    self.print_entry(entry);
-                    e_counter += 1;
+
+        // Last (in file order) displayed entry, used for cursor output
+        let mut last_entry: Option<&FormattedLogEntry> = None;
+
+        let boot_order = self.boot_order(&formatted_entries);
+
+        // Select entries to display from each boot, according to entry limits. Limits are
+        // applied before reversing, i.e. --lines selects the newest entries also when reversed
+        let mut selection = self.select_entries(&boot_order, &formatted_entries);
+        if self.parse_opt.reverse() {
+            selection.reverse();
+        }
+
+        // Column names as header row for delimited output
+        let delimiter = match self.parse_opt.output_mode() {
+            opt::OutputMode::Csv => Some(','),
+            opt::OutputMode::Tsv => Some('\t'),
+            _ => None,
+        };
+        if let Some(d) = delimiter {
+            output::print_delimited(out, self.parse_opt.columns(), d)?;
+        }
+
+        // Column widths, same for all boots
+        let layout = self.table_layout(&selection);
+
+        let mut current_host: Option<usize> = None;
+        // Boot of previous printed entry, for boot separator lines in journalctl text output
+        let mut prev_boot: Option<&String> = None;
+        for (boot, entries) in selection {
+            // print host 'header' when host changes, if grouped by host. No headers
+            // for machine readable output
+            let host_index = self.boot_host(boot);
+            if table_output && self.parse_opt.group_by_host() && host_index != current_host {
+                if let Some(index) = host_index {
+                    self.print_host(out, &self.hosts[index])?;
+                }
+                current_host = host_index;
+            }
+
+            // print current boot 'header', or timeline 'header' in flat mode
+            if !table_output {
+                // No headers for machine readable output
+            } else if self.parse_opt.flat() {
+                self.print_timeline(out, entries.len())?;
+                self.print_column_header(out, &layout)?;
+            } else {
+                self.print_boot(out, boot, &formatted_entries[boot], entries.len())?;
+                self.print_column_header(out, &layout)?;
+            }
+
+            let ordered: Box<dyn Iterator<Item = &FormattedLogEntry>> = if self.parse_opt.reverse()
+            {
+                Box::new(entries.iter().rev())
+            } else {
+                Box::new(entries.iter())
+            };
+            for entry in ordered {
+                let log_entry = &self.log_entries[entry.index];
+                let output_mode = self.parse_opt.output_mode();
+
+                // Separate boots as journalctl does, also in flat mode
+                if output_mode.is_journal_text() {
+                    if let Some(id) = log_entry._BOOT_ID.as_ref() {
+                        if prev_boot.is_some_and(|prev| prev != id) {
+                            writeln!(out, "{}", self.colors.header(&format!("-- Boot {} --", id)))?;
+                        }
+                        prev_boot = Some(id);
+                    }
+                }
+
+                match output_mode {
+                    opt::OutputMode::Table => {
+                        self.print_entry(out, entry, &layout)?;
+                        if self.parse_opt.details() {
+                            output::print_fields(out, log_entry, "       ")?;
+                        }
+                    }
+                    opt::OutputMode::Json => output::print_json(out, log_entry)?,
+                    opt::OutputMode::Csv | opt::OutputMode::Tsv => {
+                        let values: Vec<String> = self
+                            .parse_opt
+                            .columns()
+                            .iter()
+                            .map(|c| self.column_value(c, entry))
+                            .collect();
+                        output::print_delimited(out, &values, delimiter.unwrap())?;
+                    }
+                    opt::OutputMode::Template => {
+                        let line = self
+                            .parse_opt
+                            .template()
+                            .render(|field| self.template_value(field, entry));
+                        writeln!(out, "{}", line)?;
+                    }
+                    opt::OutputMode::Cat => output::print_cat(out, log_entry)?,
+                    opt::OutputMode::Verbose => output::print_verbose(out, log_entry)?,
+                    mode => output::print_short(out, log_entry, mode, &self.colors)?,
+                }
+
+                if last_entry.is_none_or(|last| entry.index > last.index) {
+                    last_entry = Some(entry);
+                }
+            }
+        }
+
+        // Print and store cursor of last displayed entry, if any was displayed
+        if let Some(last) = last_entry {
+            if self.parse_opt.show_cursor() {
+                writeln!(out, "-- cursor: {}", last.cursor)?;
+            }
+
+            if let Some(path) = self.parse_opt.cursor_file() {
+                if let Err(err) = fs::write(path, format!("{}\n", last.cursor)) {
+                    eprintln!("Failed to write cursor file '{}': {}", path, err);
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Filtered entries in the order and selection displayed by print, as a single list.
+    // Used by the interactive viewer, to display the same entries as the command line
+    pub fn view_entries(&self) -> Result<Vec<FormattedLogEntry>, failure::Error> {
+        let mut selection = self.selected_entries()?;
+
+        let mut entries = Vec::new();
+        if self.parse_opt.reverse() {
+            selection.reverse();
+            for (_, boot_entries) in selection {
+                entries.extend(boot_entries.into_iter().rev());
+            }
+        } else {
+            for (_, boot_entries) in selection {
+                entries.extend(boot_entries);
+            }
+        }
+        return Ok(entries);
+    }
+
+    pub fn log_entry(&self, index: usize) -> &LogEntry {
+        return &self.log_entries[index];
+    }
+
+    pub fn filename(&self) -> &str {
+        return &self.filename;
+    }
+
+    // Boot IDs, in chronological order
+    pub fn boot_ids(&self) -> &Vec<String> {
+        return &self.boot_ids;
+    }
+
+    // Short description of boot, "<host> <first timestamp>"
+    pub fn boot_description(&self, boot: &str) -> String {
+        match self.boots.get(boot) {
+            Some(b) => {
+                let host = &self.hosts[b.host].hostname;
+                return format!("{} {}", host, format_timestamp(b.first_timestamp));
+            }
+            None => return "N/A".to_string(),
+        }
+    }
+
+    pub fn options(&self) -> &opt::CliOptions {
+        return &self.parse_opt;
+    }
+
+    pub fn options_mut(&mut self) -> &mut opt::CliOptions {
+        return &mut self.parse_opt;
+    }
+
+    // Internal function for formatting entries and ordering entries of each boot,
+    // or merging all boots into a single timeline (flat mode, with an empty boot ID)
+    fn ordered_entries(&self) -> Result<HashMap<String, Vec<FormattedLogEntry>>, failure::Error> {
+        let mut formatted_entries = self.format_entry_list()?;
+        if self.parse_opt.flat() {
+            let mut merged: Vec<FormattedLogEntry> =
+                formatted_entries.drain().flat_map(|(_, e)| e).collect();
+            self.sort_entries(&mut merged);
+            formatted_entries.insert(String::new(), merged);
+        } else {
+            for entries in formatted_entries.values_mut() {
+                self.sort_entries(entries);
+            }
+        }
+        return Ok(formatted_entries);
+    }
+
+    // Internal function for the entries selected for display from each boot, in boot order
+    fn selected_entries(&self) -> Result<Vec<(String, Vec<FormattedLogEntry>)>, failure::Error> {
+        let formatted_entries = self.ordered_entries()?;
+        let boot_order = self.boot_order(&formatted_entries);
+        let selection = self.select_entries(&boot_order, &formatted_entries);
+        return Ok(selection
+            .into_iter()
+            .map(|(boot, entries)| (boot.clone(), entries.to_vec()))
+            .collect());
+    }
+
+    // Internal function for the entries selected for display from each boot, for the
+    // report modes. Errors are printed with the name of the report, None is returned
+    fn report_entries(&self, report: &str) -> Option<Vec<(String, Vec<FormattedLogEntry>)>> {
+        match self.selected_entries() {
+            Ok(selection) => return Some(selection),
+            Err(err) => {
+                eprintln!(
+                    "Error accord while formatting log entries [ {:?} ], unable to display {}",
+                    err, report
+                );
+                return None;
+            }
+        }
+    }
+
+    // Internal function to get order to print boots in, grouped by host if requested
+    fn boot_order<'a>(
+        &'a self,
+        formatted_entries: &'a HashMap<String, Vec<FormattedLogEntry>>,
+    ) -> Vec<&'a String> {
+        if self.parse_opt.flat() {
+            return formatted_entries.keys().collect();
+        }
+
+        if self.parse_opt.group_by_host() {
+            let mut order = Vec::new();
+            for host_index in 0..self.hosts.len() {
+                for id in &self.boot_ids {
+                    if self.boot_host(id) == Some(host_index) && formatted_entries.contains_key(id)
+                    {
+                        order.push(id);
+                    }
+                }
+            }
+            return order;
+        }
+
+        return self
+            .boot_ids
+            .iter()
+            .filter(|id| formatted_entries.contains_key(*id))
+            .collect();
+    }
+
+    // Internal function for ordering entries according to sort key.
+    // Entries are kept in file order if no sort key is given, except in flat mode where
+    // entries from different boots and files are ordered by time.
+    fn sort_entries(&self, entries: &mut [FormattedLogEntry]) {
+        let sort_key = match self.parse_opt.sort_key() {
+            Some(key) => Some(key),
+            None if self.parse_opt.flat() => Some(opt::SortKey::Time),
+            None => None,
+        };
+
+        // File order (index) is used to keep the order stable for equal keys
+        match sort_key {
+            Some(opt::SortKey::Time) => entries.sort_by_key(|e| (e.realtime, e.index)),
+            Some(opt::SortKey::Seqnum) => {
+                entries.sort_by_key(|e| (e.seqnum.unwrap_or(u64::MAX), e.index))
+            }
+            Some(opt::SortKey::Priority) => {
+                entries.sort_by_key(|e| (e.priority.unwrap_or(u32::MAX), e.realtime, e.index))
+            }
+            None => (),
+        }
+    }
+
+    // Internal function for selecting the entries to display from each boot, in boot order.
+    // The first (--number) or last (--lines) entries are selected from each boot, or from
+    // all boots together if the limit is overall. Boots with nothing selected by an overall
+    // limit are left out.
+    fn select_entries<'a>(
+        &self,
+        boot_order: &[&'a String],
+        formatted_entries: &'a HashMap<String, Vec<FormattedLogEntry>>,
+    ) -> Vec<(&'a String, &'a [FormattedLogEntry])> {
+        let head_limit = self.parse_opt.num_of_entries() as usize;
+        let tail_limit = self.parse_opt.num_of_lines() as usize;
+        let mut selection = Vec::new();
+
+        if !self.parse_opt.limit_overall() {
+            for boot in boot_order {
+                let entries = formatted_entries[*boot].as_slice();
+                let selected = if head_limit > 0 {
+                    &entries[..head_limit.min(entries.len())]
+                } else if tail_limit > 0 {
+                    &entries[entries.len().saturating_sub(tail_limit)..]
                 } else {
-                    break;
+                    entries
+                };
+                selection.push((*boot, selected));
+            }
+            return selection;
+        }
+
+        // Overall limit, count entries over all boots in boot order
+        let total: usize = boot_order.iter().map(|b| formatted_entries[*b].len()).sum();
+        let (mut skip, mut take) = if head_limit > 0 {
+            (0, head_limit)
+        } else if tail_limit > 0 {
+            (total.saturating_sub(tail_limit), tail_limit)
+        } else {
+            (0, total)
+        };
+
+        for boot in boot_order {
+            let entries = formatted_entries[*boot].as_slice();
+            let start = skip.min(entries.len());
+            let end = (start + take).min(entries.len());
+            skip -= start;
+            take -= end - start;
+
+            if end > start {
+                selection.push((*boot, &entries[start..end]));
+            }
+        }
+        return selection;
+    }
+
+    // Internal function for printing boot IDs list, in chronological order with the
+    // index offset (0 = last boot), time range, entry count, host and kernel version
+    fn print_boot_list(self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "'{}'", self.filename)?;
+        writeln!(out, "Contains following Boot IDs:")?;
+        writeln!(
+            out,
+            "{0: >4}  {1: <32}  {2: <19}  {3: <19}  {4: >10}  {5: >7}  {6: <16}  KERNEL",
+            "IDX", "BOOT ID", "FIRST ENTRY", "LAST ENTRY", "DURATION", "ENTRIES", "HOST"
+        )?;
+
+        let last_index = self.boot_ids.len() as i64 - 1;
+        for (index, id) in self.boot_ids.iter().enumerate() {
+            let boot = &self.boots[id];
+
+            writeln!(
+                out,
+                "{0: >4}  {1: <32}  {2: <19}  {3: <19}  {4: >10}  {5: >7}  {6: <16}  {7}",
+                index as i64 - last_index,
+                id,
+                format_timestamp(boot.first_timestamp),
+                format_timestamp(boot.last_timestamp),
+                format_duration(boot.first_timestamp, boot.last_timestamp),
+                boot.entries,
+                self.hosts[boot.host].hostname,
+                boot.kernel_version.as_deref().unwrap_or("N/A")
+            )?;
+        }
+        return Ok(());
+    }
+
+    // Internal function for printing hosts list, with the boots of each host
+    fn print_host_list(self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "'{}'", self.filename)?;
+        writeln!(out, "Contains following hosts:")?;
+        for (index, host) in self.hosts.iter().enumerate() {
+            writeln!(out, "{} (machine ID: {})", host.hostname, host.machine_id)?;
+            for id in &self.boot_ids {
+                if self.boot_host(id) == Some(index) {
+                    writeln!(out, "    {}", id)?;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Get value of an output column for entry, either a computed column or a journal field.
+    // Missing fields give an empty value.
+    fn column_value(&self, column: &str, entry: &FormattedLogEntry) -> String {
+        let log_entry = &self.log_entries[entry.index];
+        match column {
+            opt::COLUMN_TIME => return entry.timestamp.clone(),
+            opt::COLUMN_SEQNUM => return entry.sequence_number.clone(),
+            opt::COLUMN_BOOT => return log_entry._BOOT_ID.clone().unwrap_or_default(),
+            opt::COLUMN_PRIORITY => {
+                return match entry.priority {
+                    Some(level) if (level as usize) < opt::PRIORITY_NAMES.len() => {
+                        opt::PRIORITY_NAMES[level as usize].to_string()
+                    }
+                    _ => String::new(),
                 }
             }
+            opt::COLUMN_UNIT => return log_entry.unit_name().cloned().unwrap_or_default(),
+            field => return log_entry.field(field).unwrap_or_default(),
+        }
+    }
+
+    // Get value of a format template field for entry, with value format applied
+    fn template_value(&self, field: &output::TemplateField, entry: &FormattedLogEntry) -> String {
+        match (field.name.as_str(), field.format.as_deref()) {
+            (opt::COLUMN_TIME, Some(time_format)) => {
+                return format_time(entry.realtime, time_format)
+            }
+            (opt::COLUMN_PRIORITY, Some("num")) => {
+                return entry.priority.map(|p| p.to_string()).unwrap_or_default()
+            }
+            (name, _) => return self.column_value(name, entry),
         }
     }
 
-    // Internal function for printing boot IDs list
-    fn print_boot_list(self) {
-        println!("'{}'", self.filename);
-        println!("Contains following Boot IDs:");
-        for id in self.boot_ids {
-            println!("{}", id);
+    // Get index of host for boot, None if boot isn't in logfile
+    fn boot_host(&self, boot: &str) -> Option<usize> {
+        return self.boots.get(boot).map(|b| b.host);
+    }
+
+    // Internal function to get hostname of boot, N/A if unknown
+    fn boot_hostname(&self, boot: &str) -> String {
+        match self.boot_host(boot) {
+            Some(index) => return self.hosts[index].hostname.clone(),
+            None => return "N/A".to_string(),
         }
     }
 
@@ -206,12 +804,17 @@ impl ParsedLogfile {
     // returns a map <bootid, vector of entries to print>
     fn format_entry_list(&self) -> Result<HashMap<String, Vec<FormattedLogEntry>>, failure::Error> {
         let mut entry_list: HashMap<String, Vec<FormattedLogEntry>> = HashMap::new();
+        // Monotonic timestamp of the previous formatted entry for each boot, used for deltas
+        let mut prev_monotonic: HashMap<String, u64> = HashMap::new();
 
         // Check if boot filter should be used when creating HashMap keys
         let boot_list_filter = self.parse_opt.boot_filter();
-        if boot_list_filter.len() != 0 {
+        if !boot_list_filter.is_empty() {
             //only get entries from specified boot(s)
             for id in boot_list_filter {
+                if !self.boots.contains_key(id) {
+                    eprintln!("Boot ID {} not found in logfile", id);
+                }
                 entry_list.insert(id.clone(), Vec::new());
             }
         } else {
@@ -221,8 +824,11 @@ impl ParsedLogfile {
             }
         }
 
+        // Get first entry to display, if cursor is provided
+        let start_index = self.cursor_start_index()?;
+
         // Loop through all entries and format them according to filters
-        for e in &self.log_entries {
+        for (e_index, e) in self.log_entries.iter().enumerate().skip(start_index) {
             // tmp variable for string comparison against filters, maybe not beautiful..
             let empty_string = "".to_string();
             let na = "N/A".to_string();
@@ -242,15 +848,9 @@ impl ParsedLogfile {
                 }
             }
 
-            let mut e_unit = match e.UNIT {
-                Some(ref u_name) => u_name,
-                None => match e._SYSTEMD_UNIT {
-                    Some(ref systemd_unit_name) => systemd_unit_name,
-                    None => match e.SYSLOG_IDENTIFIER {
-                        Some(ref syslog_name) => syslog_name,
-                        None => &empty_string,
-                    },
-                },
+            let mut e_unit = match e.unit_name() {
+                Some(u_name) => u_name,
+                None => &empty_string,
             };
 
             if e_unit.is_empty() {
@@ -259,23 +859,87 @@ impl ParsedLogfile {
             }
 
             // Check if kernel flag is set, if set check if entry is a kernel print
-            if self.parse_opt.kernel_flag() {
-                if e_unit != &"kernel".to_string() {
+            if self.parse_opt.kernel_flag() && e_unit != "kernel" {
+                continue;
+            }
+
+            // Check against unit filter, if specified
+            let unit_filter = self.parse_opt.unit_filter();
+            if !unit_filter.is_empty() && !unit_filter.contains(e_unit) {
+                // not in unit list
+                continue;
+            }
+
+            // Check against facility, transport, PID and UID filters, if specified.
+            // Entries without the field never match
+            let facility_filter = self.parse_opt.facility_filter();
+            if !facility_filter.is_empty() {
+                let facility = e
+                    .SYSLOG_FACILITY
+                    .as_ref()
+                    .and_then(|f| f.parse::<u32>().ok());
+                if !facility.is_some_and(|f| facility_filter.contains(&f)) {
                     continue;
                 }
             }
 
-            // Check against unit filter, if specified
-            let unit_filter = &self.parse_opt.unit_filter();
-            if unit_filter.len() != 0 {
-                if !unit_filter.contains(&e_unit) {
-                    // not in unit list
+            let transport_filter = self.parse_opt.transport_filter();
+            if !transport_filter.is_empty()
+                && !e
+                    ._TRANSPORT
+                    .as_ref()
+                    .is_some_and(|t| transport_filter.contains(t))
+            {
+                continue;
+            }
+
+            let pid_filter = self.parse_opt.pid_filter();
+            if !pid_filter.is_empty() {
+                let pid = e._PID.as_ref().and_then(|p| p.parse::<u32>().ok());
+                if !pid.is_some_and(|p| pid_filter.contains(&p)) {
+                    continue;
+                }
+            }
+
+            let host_filter = self.parse_opt.host_filter();
+            if !host_filter.is_empty()
+                && !e
+                    ._HOSTNAME
+                    .as_ref()
+                    .is_some_and(|h| host_filter.contains(h))
+            {
+                continue;
+            }
+
+            let machine_filter = self.parse_opt.machine_filter();
+            if !machine_filter.is_empty()
+                && !e
+                    ._MACHINE_ID
+                    .as_ref()
+                    .is_some_and(|m| machine_filter.contains(m))
+            {
+                continue;
+            }
+
+            let uid_filter = self.parse_opt.uid_filter();
+            if !uid_filter.is_empty() {
+                let uid = e._UID.as_ref().and_then(|u| u.parse::<u32>().ok());
+                let in_range = |u: u32| uid_filter.iter().any(|(from, to)| u >= *from && u <= *to);
+                if !uid.is_some_and(in_range) {
+                    continue;
+                }
+            }
+
+            // Check message against pattern, if specified
+            if let Some(pattern) = self.parse_opt.grep() {
+                if !e.MESSAGE.as_ref().is_some_and(|m| pattern.is_match(m)) {
                     continue;
                 }
             }
 
             // Check loglevel
-            let log_level_lim = self.parse_opt.log_level();
+            let log_level_from = self.parse_opt.log_level_from();
+            let log_level_to = self.parse_opt.log_level_to();
             let mut e_log_level = match e.PRIORITY {
                 Some(ref p) => p,
                 None => &empty_string,
@@ -286,8 +950,8 @@ impl ParsedLogfile {
                 e_log_level = &na;
             } else {
                 let log_level_int = e_log_level.parse::<u32>()?;
-                if log_level_int > log_level_lim {
-                    // Log level higher (less priority) then filter
+                if log_level_int < log_level_from || log_level_int > log_level_to {
+                    // Log level outside of filter range
                     continue;
                 }
             }
@@ -298,13 +962,12 @@ impl ParsedLogfile {
                 None => &empty_string,
             };
 
-            let e_seq_nr: String;
-            if e_log_cursor_string.is_empty() {
+            let e_seq_nr: String = if e_log_cursor_string.is_empty() {
                 eprintln!("Unable to cursor string for entry");
-                e_seq_nr = na.clone();
+                na.clone()
             } else {
-                e_seq_nr = self.get_entry_nr(&e_log_cursor_string)?;
-            }
+                self.get_entry_nr(e_log_cursor_string)?
+            };
 
             // Get PID
             let e_pid_nr = match &e._PID {
@@ -314,8 +977,7 @@ impl ParsedLogfile {
 
             let b_o: &str = "(";
             let b_c: &str = ")";
-            let unit_with_pid =
-                e_unit.to_owned() + &b_o.to_owned() + &e_pid_nr.to_owned() + &b_c.to_owned();
+            let unit_with_pid = e_unit.to_owned() + b_o + e_pid_nr + b_c;
 
             // Get timestamp
             let e_rt_ts = match e.__REALTIME_TIMESTAMP {
@@ -382,22 +1044,56 @@ impl ParsedLogfile {
                 let day_time_filter =
                     NaiveTime::from_num_seconds_from_midnight(stop_time_filter as u32, 0);
 
-                if day_time_entry.hour() > day_time_filter.hour() {
+                // Entry time is later then filter, ignore entry
+                if day_time_entry > day_time_filter {
                     continue;
-                } else if day_time_entry.hour() == day_time_filter.hour() {
-                    if day_time_entry.minute() > day_time_filter.minute() {
-                        continue;
-                    } else if day_time_entry.minute() == day_time_filter.minute() {
-                        if day_time_entry.second() > day_time_filter.second() {
-                            continue;
-                        }
-                    }
                 }
             }
 
-            // Format entry timestamp
-            let formatted_timestamp = UTC.timestamp(since_utc_s, 0);
-            let formatted_timestamp = formatted_timestamp.format("%Y-%m-%d %H:%M:%S").to_string();
+            // Get monotonic timestamp, microseconds since boot. Invalid timestamps
+            // are handled as missing, the entry is displayed with N/A
+            let e_mono_ts = e
+                .__MONOTONIC_TIMESTAMP
+                .as_ref()
+                .and_then(|timestamp| timestamp.parse::<u64>().ok());
+
+            // Check against boot time (monotonic) range filter, entries without
+            // a monotonic timestamp can't be placed in the range and are ignored
+            if let Some(from) = self.parse_opt.boot_time_from() {
+                match e_mono_ts {
+                    Some(ts) if ts >= from => (),
+                    _ => continue,
+                }
+            }
+            if let Some(to) = self.parse_opt.boot_time_to() {
+                match e_mono_ts {
+                    Some(ts) if ts <= to => (),
+                    _ => continue,
+                }
+            }
+
+            // Format entry timestamp according to display mode
+            let formatted_timestamp = match self.parse_opt.timestamp_mode() {
+                opt::TimestampMode::Realtime if self.parse_opt.precise() => {
+                    format_time(e_rt_ts, "%Y-%m-%d %H:%M:%S%.6f")
+                }
+                opt::TimestampMode::Realtime => format_timestamp(Some(e_rt_ts)),
+                opt::TimestampMode::Monotonic => match e_mono_ts {
+                    Some(ts) => format!("[{:>5}.{:06}]", ts / 1000000, ts % 1000000),
+                    None => na.clone(),
+                },
+                opt::TimestampMode::Delta => match e_mono_ts {
+                    Some(ts) => {
+                        // First entry of a boot has no previous entry, delta is 0
+                        let delta = match prev_monotonic.insert(e_boot_id.clone(), ts) {
+                            Some(prev) => ts.saturating_sub(prev),
+                            None => 0,
+                        };
+                        format!("+{}.{:06}", delta / 1000000, delta % 1000000)
+                    }
+                    None => na.clone(),
+                },
+            };
 
             // Get entry log message
             let msg = match e.MESSAGE {
@@ -408,51 +1104,580 @@ impl ParsedLogfile {
             // Add handling of errno? For the future...
 
             // Add formatted entry to vector for correct boot
-            match entry_list.get_mut(e_boot_id) {
-                Some(boot) => boot.push(FormattedLogEntry {
+            if let Some(boot) = entry_list.get_mut(e_boot_id) {
+                boot.push(FormattedLogEntry {
+                    index: e_index,
+                    cursor: e_log_cursor_string.clone(),
+                    seqnum: e_seq_nr.parse::<u64>().ok(),
+                    realtime: e_rt_ts,
+                    priority: e_log_level.parse::<u32>().ok(),
                     sequence_number: e_seq_nr,
                     timestamp: formatted_timestamp,
                     loglevel: e_log_level.clone(),
                     unit_name: unit_with_pid,
+                    unit: e_unit.clone(),
+                    pid: e_pid_nr.clone(),
                     message: msg.clone(),
-                }),
-                None => (),
+                })
             }
         } // for loop
 
         return Ok(entry_list);
     }
 
+    // Get column widths of table output from the entries to display. The unit column is
+    // limited to a quarter of the terminal width, longer unit names are ellipsized
+    fn table_layout(&self, selection: &[(&String, &[FormattedLogEntry])]) -> TableLayout {
+        let shown = |column: &str| !self.parse_opt.hidden_columns().iter().any(|c| c == column);
+        let pid = shown(opt::HIDE_PID);
+
+        let mut seq = "Seq#".len();
+        let mut time = self.timestamp_label().len();
+        let mut level = "LVL".len();
+        let mut unit = "Unit".len();
+        for entry in selection.iter().flat_map(|(_, entries)| entries.iter()) {
+            seq = seq.max(entry.sequence_number.chars().count());
+            time = time.max(entry.timestamp.chars().count());
+            level = level.max(entry.loglevel.chars().count());
+            unit = unit.max(self.unit_column(entry, pid, usize::MAX).chars().count());
+        }
+        let unit_max = match self.parse_opt.terminal_width() {
+            Some(width) => (width / 4).max(18),
+            None => usize::MAX,
+        };
+
+        return TableLayout {
+            seq: Some(seq).filter(|_| shown(opt::HIDE_SEQ)),
+            time: Some(time).filter(|_| shown(opt::HIDE_TIME)),
+            level: Some(level).filter(|_| shown(opt::HIDE_PRIORITY)),
+            unit: Some(unit.min(unit_max)).filter(|_| shown(opt::HIDE_UNIT)),
+            pid,
+        };
+    }
+
+    // Get unit name of entry, with PID if shown, ellipsized to max width
+    fn unit_column(&self, entry: &FormattedLogEntry, pid: bool, width: usize) -> String {
+        if !pid {
+            return output::truncate(&entry.unit, width);
+        }
+        let pid = format!("({})", entry.pid);
+        let unit_width = width.saturating_sub(pid.chars().count());
+        return output::truncate(&entry.unit, unit_width) + &pid;
+    }
+
     // Print format header
-    fn print_column_header(&self) {
-        println!(
-            "{0: <5}  {1: <20}  {2: <5}  {3: <18}  {4: }",
-            "Seq#", "Datetime", "LVL", "Unit", "Message"
+    fn print_column_header(&self, out: &mut dyn Write, layout: &TableLayout) -> io::Result<()> {
+        let mut header = String::new();
+        if let Some(width) = layout.seq {
+            header += &format!("{: <width$}  ", "Seq#", width = width);
+        }
+        if let Some(width) = layout.time {
+            header += &format!("{: <width$}  ", self.timestamp_label(), width = width);
+        }
+        if let Some(width) = layout.level {
+            header += &format!("{: <width$}  ", "LVL", width = width);
+        }
+        if let Some(width) = layout.unit {
+            header += &format!("{: <width$}   ", "Unit", width = width);
+        }
+        writeln!(out, "{}Message", header)?;
+        return Ok(());
+    }
+
+    // Get timestamp column label for the current timestamp mode
+    fn timestamp_label(&self) -> &'static str {
+        match self.parse_opt.timestamp_mode() {
+            opt::TimestampMode::Realtime => return "Datetime",
+            opt::TimestampMode::Monotonic => return "Since boot",
+            opt::TimestampMode::Delta => return "Delta",
+        }
+    }
+
+    // Print merged timeline header, for flat mode
+    fn print_timeline(&self, out: &mut dyn Write, displayed: usize) -> io::Result<()> {
+        let timeline = format!("Timeline : all boots, {} entries displayed", displayed);
+        return self.print_section_header(out, &timeline, SECTION_LINE);
+    }
+
+    // Print statistics of the entries selected for display, per boot and overall.
+    // Per boot statistics are left out in flat mode
+    fn print_stats(self, out: &mut dyn Write) -> io::Result<()> {
+        let selection = match self.report_entries("statistics") {
+            Some(selection) => selection,
+            None => return Ok(()),
+        };
+        let top = self.parse_opt.top_count();
+
+        let mut overall = stats::Stats::default();
+        let mut boots: Vec<stats::BootStats> = Vec::new();
+        for (boot, entries) in selection {
+            let mut boot_stats = stats::Stats::default();
+            for entry in &entries {
+                let log_entry = &self.log_entries[entry.index];
+                boot_stats.add(log_entry, entry);
+                overall.add(log_entry, entry);
+            }
+            if self.parse_opt.flat() {
+                continue;
+            }
+            boot_stats.finish(top);
+            boots.push(stats::BootStats {
+                host: self.boot_hostname(&boot),
+                boot,
+                stats: boot_stats,
+            });
+        }
+        overall.finish(top);
+
+        if self.parse_opt.output_mode() == opt::OutputMode::Json {
+            let report = stats::StatsReport {
+                malformed_lines: self.malformed_lines,
+                overall,
+                boots,
+            };
+            return print_json_report(out, &report);
+        }
+
+        for boot in &boots {
+            let header = format!("Boot : ID: {}, Host: {}", boot.boot, boot.host);
+            self.print_section_header(out, &header, SECTION_LINE)?;
+            boot.stats.print(out)?;
+        }
+        let header = format!(
+            "Overall : '{}', {} malformed lines ignored",
+            self.filename, self.malformed_lines
+        );
+        self.print_section_header(out, &header, BANNER_LINE)?;
+        overall.print(out)?;
+        return Ok(());
+    }
+
+    // Print templates of the messages selected for display, most severe and frequent first
+    fn print_digest(self, out: &mut dyn Write) -> io::Result<()> {
+        let selection = match self.report_entries("digest") {
+            Some(selection) => selection,
+            None => return Ok(()),
+        };
+
+        let mut digest = digest::Digest::new();
+        let mut entries = 0;
+        for (_, boot_entries) in &selection {
+            for entry in boot_entries {
+                digest.add(entry);
+                entries += 1;
+            }
+        }
+        let templates = digest.templates();
+
+        if self.parse_opt.output_mode() == opt::OutputMode::Json {
+            return print_json_report(out, &templates);
+        }
+
+        let header = format!(
+            "Digest : '{}', {} entries, {} message templates",
+            self.filename,
+            entries,
+            templates.len()
         );
+        self.print_section_header(out, &header, SECTION_LINE)?;
+
+        let count_width = templates
+            .iter()
+            .map(|t| t.count.to_string().len())
+            .max()
+            .unwrap_or(0);
+        for template in &templates {
+            let priority = opt::priority_name(template.priority);
+            let units: Vec<String> = template.units.iter().map(|u| self.colors.unit(u)).collect();
+            writeln!(
+                out,
+                "{} {: >count_width$}x  {} - {}  {}",
+                self.colors
+                    .priority(&format!("{: <7}", priority), template.priority),
+                template.count,
+                format_timestamp(Some(template.first)),
+                format_timestamp(Some(template.last)),
+                units.join(", "),
+                count_width = count_width
+            )?;
+            for line in self.message_lines(&template.template, 4) {
+                writeln!(out, "    {}", line)?;
+            }
+            if template.example != template.template {
+                let example = format!("e.g. {}", template.example);
+                for line in self.message_lines(&example, 4) {
+                    writeln!(out, "    {}", self.colors.priority(&line, Some(7)))?;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Print differences between the entries selected for display of two sides: the two
+    // boots of the boot filter, the two logfiles, or the last two boots in that order
+    fn print_diff(self, out: &mut dyn Write) -> io::Result<()> {
+        let boot_filter = self.parse_opt.boot_filter();
+        let by_file = boot_filter.len() != 2 && self.file_ends.len() == 2;
+        let sides: Vec<String> = if boot_filter.len() == 2 {
+            boot_filter.clone()
+        } else if by_file {
+            self.parse_opt.logfile_names().clone()
+        } else if boot_filter.is_empty() && self.boot_ids.len() >= 2 {
+            self.boot_ids[self.boot_ids.len() - 2..].to_vec()
+        } else {
+            eprintln!(
+                "Unable to display diff, expected two boots (--boot <A> --boot <B>), two logfiles or a logfile with at least two boots"
+            );
+            return Ok(());
+        };
+
+        let selection = match self.report_entries("diff") {
+            Some(selection) => selection,
+            None => return Ok(()),
+        };
+
+        let names: Vec<String> = sides
+            .iter()
+            .map(|side| {
+                if by_file {
+                    return format!("logfile '{}'", side);
+                }
+                return format!("boot {} ({})", side, self.boot_description(side));
+            })
+            .collect();
+        let mut a = diff::DiffSide::new(names[0].clone());
+        let mut b = diff::DiffSide::new(names[1].clone());
+        let normalizer = digest::Normalizer::new();
+        for (_, entries) in &selection {
+            for entry in entries {
+                let log_entry = &self.log_entries[entry.index];
+                let side = if by_file {
+                    self.file_ends.iter().position(|end| entry.index < *end)
+                } else {
+                    log_entry
+                        ._BOOT_ID
+                        .as_ref()
+                        .and_then(|id| sides.iter().position(|s| s == id))
+                };
+                match side {
+                    Some(0) => a.add(&normalizer, log_entry, entry),
+                    Some(1) => b.add(&normalizer, log_entry, entry),
+                    _ => {}
+                }
+            }
+        }
+        let report = diff::DiffReport::new(a, b);
+
+        if self.parse_opt.output_mode() == opt::OutputMode::Json {
+            return print_json_report(out, &report);
+        }
+
+        let header = format!("Diff : '{}'", self.filename);
+        self.print_section_header(out, &header, SECTION_LINE)?;
+        return report.print(out, &self.colors);
     }
 
-    // Print Current boot
-    fn print_boot(&self, boot: &String, num: usize) {
-        println!("- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -");
-        println!("Boot : ID: {}, Number of parsed entries: {}", boot, num);
-        println!("- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -");
+    // Print lifecycle of units in each boot, from the entries selected for display.
+    // Entries are grouped by boot also in flat mode, restarts are counted per boot
+    fn print_units_report(self, out: &mut dyn Write) -> io::Result<()> {
+        let selection = match self.report_entries("units report") {
+            Some(selection) => selection,
+            None => return Ok(()),
+        };
+
+        let mut boots: Vec<lifecycle::BootUnits> = Vec::new();
+        for (_, entries) in &selection {
+            for entry in entries {
+                let log_entry = &self.log_entries[entry.index];
+                let boot_id = match log_entry._BOOT_ID.as_ref() {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let index = match boots.iter().position(|b| &b.boot == boot_id) {
+                    Some(index) => index,
+                    None => {
+                        let host = self.boot_hostname(boot_id);
+                        boots.push(lifecycle::BootUnits::new(boot_id.clone(), host));
+                        boots.len() - 1
+                    }
+                };
+                boots[index].add(log_entry, entry);
+            }
+        }
+
+        // Boots in chronological order, as in the boot list
+        boots.sort_by_key(|b| self.boot_ids.iter().position(|id| *id == b.boot));
+        for boot in boots.iter_mut() {
+            boot.finish();
+        }
+
+        if self.parse_opt.output_mode() == opt::OutputMode::Json {
+            return print_json_report(out, &boots);
+        }
+
+        for boot in &boots {
+            let header = format!("Boot : ID: {}, Host: {}", boot.boot, boot.host);
+            self.print_section_header(out, &header, SECTION_LINE)?;
+            boot.print(out, &self.colors)?;
+        }
+        return Ok(());
     }
 
-    // Function for the print of entry
-    fn print_entry(&self, log_entry: &FormattedLogEntry) {
-        println!(
-            "{0: <5}  {1: <20}  {2: <5}  {3: <18}   {4: }",
-            log_entry.sequence_number,
-            log_entry.timestamp,
-            log_entry.loglevel,
-            log_entry.unit_name,
-            log_entry.message
+    // Print histogram of the entries selected for display, all boots in one timeline
+    fn print_histogram(self, out: &mut dyn Write) -> io::Result<()> {
+        let selection = match self.report_entries("histogram") {
+            Some(selection) => selection,
+            None => return Ok(()),
+        };
+        let entries: Vec<&FormattedLogEntry> =
+            selection.iter().flat_map(|(_, e)| e.iter()).collect();
+
+        let histogram =
+            match histogram::Histogram::new(&entries, self.parse_opt.histogram_interval()) {
+                Ok(h) => h,
+                Err(err) => {
+                    eprintln!("Unable to display histogram: {}", err);
+                    return Ok(());
+                }
+            };
+
+        if self.parse_opt.output_mode() == opt::OutputMode::Json {
+            return print_json_report(out, &histogram.report());
+        }
+
+        let header = format!(
+            "Histogram : '{}', {} entries, interval {}",
+            self.filename,
+            histogram.entries(),
+            histogram.interval()
+        );
+        self.print_section_header(out, &header, SECTION_LINE)?;
+        let width = self.parse_opt.terminal_width().unwrap_or(80);
+        return histogram.print(
+            out,
+            width,
+            self.parse_opt.stacked(),
+            self.parse_opt.ascii(),
+            &self.colors,
         );
     }
 
+    // Print header of report section between separator lines
+    fn print_section_header(
+        &self,
+        out: &mut dyn Write,
+        header: &str,
+        separator: &str,
+    ) -> io::Result<()> {
+        writeln!(out, "{}", separator)?;
+        writeln!(out, "{}", self.colors.header(header))?;
+        writeln!(out, "{}", separator)?;
+        return Ok(());
+    }
+
+    // Print Current host
+    fn print_host(&self, out: &mut dyn Write, host: &HostInfo) -> io::Result<()> {
+        let header = format!("Host : {}, Machine ID: {}", host.hostname, host.machine_id);
+        return self.print_section_header(out, &header, BANNER_LINE);
+    }
+
+    // Print Current boot header,
+    // with statistics: entries in boot, entries matching filters, entries left out by
+    // entry limits, time range of boot and matching entries per priority
+    fn print_boot(
+        &self,
+        out: &mut dyn Write,
+        boot: &str,
+        matched: &[FormattedLogEntry],
+        displayed: usize,
+    ) -> io::Result<()> {
+        let info = self.boots.get(boot);
+        let host = match info {
+            Some(b) => self.hosts[b.host].hostname.as_str(),
+            None => "N/A",
+        };
+        let boot_entries = info.map_or(0, |b| b.entries);
+        let (first, last) = match info {
+            Some(b) => (b.first_timestamp, b.last_timestamp),
+            None => (None, None),
+        };
+
+        // Count matching entries per priority, entries without priority are counted as N/A
+        let mut priority_count = [0usize; 8];
+        let mut no_priority = 0;
+        for entry in matched {
+            match entry.loglevel.parse::<usize>() {
+                Ok(level) if level < priority_count.len() => priority_count[level] += 1,
+                _ => no_priority += 1,
+            }
+        }
+        let mut priorities: Vec<String> = priority_count
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(level, count)| format!("{}: {}", opt::PRIORITY_NAMES[level], count))
+            .collect();
+        if no_priority > 0 {
+            priorities.push(format!("N/A: {}", no_priority));
+        }
+
+        // Boot statistics are printed between the header and the closing separator
+        writeln!(out, "{}", SECTION_LINE)?;
+        let header = format!("Boot : ID: {}, Host: {}", boot, host);
+        writeln!(out, "{}", self.colors.header(&header))?;
+        writeln!(
+            out,
+            "Entries : {} in boot, {} matching filters, {} not displayed due to entry limit",
+            boot_entries,
+            matched.len(),
+            matched.len() - displayed
+        )?;
+        writeln!(
+            out,
+            "Time : {} - {} ({})",
+            format_timestamp(first),
+            format_timestamp(last),
+            format_duration(first, last)
+        )?;
+        if !priorities.is_empty() {
+            writeln!(out, "Priority : {}", priorities.join(", "))?;
+        }
+        writeln!(out, "{}", SECTION_LINE)?;
+        return Ok(());
+    }
+
+    // Function for the print of entry, level and message colored by priority.
+    // Columns are padded before coloring to keep the alignment
+    // Multi-line messages are printed with continuation lines indented to the message column
+    fn print_entry(
+        &self,
+        out: &mut dyn Write,
+        log_entry: &FormattedLogEntry,
+        layout: &TableLayout,
+    ) -> io::Result<()> {
+        // Columns are padded before coloring, indent is the start of the message column
+        let mut columns = String::new();
+        let mut indent = 0;
+        if let Some(width) = layout.seq {
+            columns += &format!("{: <width$}  ", log_entry.sequence_number, width = width);
+            indent += width + 2;
+        }
+        if let Some(width) = layout.time {
+            columns += &format!("{: <width$}  ", log_entry.timestamp, width = width);
+            indent += width + 2;
+        }
+        if let Some(width) = layout.level {
+            let loglevel = format!("{: <width$}", log_entry.loglevel, width = width);
+            columns += &self.colors.priority(&loglevel, log_entry.priority);
+            columns += "  ";
+            indent += width + 2;
+        }
+        if let Some(width) = layout.unit {
+            let unit_name = format!(
+                "{: <width$}",
+                self.unit_column(log_entry, layout.pid, width),
+                width = width
+            );
+            columns += &self.colors.unit(&unit_name);
+            columns += "   ";
+            indent += width + 3;
+        }
+        let lines = self.message_lines(&log_entry.message, indent);
+
+        writeln!(
+            out,
+            "{}{}",
+            columns,
+            self.colors.priority(&lines[0], log_entry.priority)
+        )?;
+        for line in &lines[1..] {
+            writeln!(
+                out,
+                "{:indent$}{}",
+                "",
+                self.colors.priority(line, log_entry.priority),
+                indent = indent
+            )?;
+        }
+        return Ok(());
+    }
+
+    // Split message into lines to print in the message column starting at indent,
+    // long lines are cut or wrapped at terminal width according to line mode
+    fn message_lines(&self, message: &str, indent: usize) -> Vec<String> {
+        // Leave some room for the message also in narrow terminals, 80 columns are used if
+        // output is not a terminal
+        let width = self
+            .parse_opt
+            .terminal_width()
+            .unwrap_or(80)
+            .saturating_sub(indent)
+            .max(20);
+
+        let mut lines = Vec::new();
+        for line in message.lines() {
+            match self.parse_opt.line_mode() {
+                opt::LineMode::Full => lines.push(line.to_string()),
+                opt::LineMode::Truncate => lines.push(output::truncate(line, width)),
+                opt::LineMode::Wrap => lines.extend(output::wrap(line, width)),
+            }
+        }
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        return lines;
+    }
+
+    // Get index of the first entry to display based on the cursor option. The cursor is
+    // looked up by exact match, if not present in the file the first entry with a
+    // later sequence number from the same journal file (seqnum ID) is used.
+    fn cursor_start_index(&self) -> Result<usize, failure::Error> {
+        let cursor = match self.parse_opt.cursor() {
+            Some(c) => c,
+            None => return Ok(0),
+        };
+        let offset = if self.parse_opt.after_cursor() { 1 } else { 0 };
+
+        let position = self
+            .log_entries
+            .iter()
+            .position(|e| e.__CURSOR.as_ref() == Some(cursor));
+        if let Some(pos) = position {
+            return Ok(pos + offset);
+        }
+
+        // Fall back on sequence number comparison
+        let seqnum_id = cursor_field(cursor, "s");
+        let seqnum = cursor_field(cursor, "i").and_then(|i| u64::from_str_radix(i, 16).ok());
+        let (seqnum_id, seqnum) = match (seqnum_id, seqnum) {
+            (Some(id), Some(nr)) => (id, nr),
+            _ => bail!("Invalid cursor: {}", cursor),
+        };
+
+        for (index, e) in self.log_entries.iter().enumerate() {
+            let e_cursor = match e.__CURSOR {
+                Some(ref c) => c,
+                None => continue,
+            };
+            if cursor_field(e_cursor, "s") != Some(seqnum_id) {
+                continue;
+            }
+            let e_seqnum =
+                cursor_field(e_cursor, "i").and_then(|i| u64::from_str_radix(i, 16).ok());
+            if let Some(nr) = e_seqnum {
+                if nr > seqnum || (nr == seqnum && offset == 0) {
+                    return Ok(index);
+                }
+            }
+        }
+
+        // All entries are before the cursor, nothing to display
+        return Ok(self.log_entries.len());
+    }
+
     // function for returning the entry number
-    fn get_entry_nr(&self, cursor_string: &String) -> Result<String, std::num::ParseIntError> {
-        let cursor_v: Vec<&str> = cursor_string.split(";").collect();
+    fn get_entry_nr(&self, cursor_string: &str) -> Result<String, std::num::ParseIntError> {
+        let cursor_v: Vec<&str> = cursor_string.split(';').collect();
         let pos = cursor_v[1].to_string();
         let seq_nr = pos.trim_start_matches("i=");
 
@@ -462,3 +1687,53 @@ impl ParsedLogfile {
         };
     }
 }
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+// Print report as a single pretty printed json document, the json output of all reports
+fn print_json_report<T: Serialize>(out: &mut dyn Write, report: &T) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, report)?;
+    writeln!(out)?;
+    return Ok(());
+}
+
+// Format realtime timestamp (microseconds since epoch) as UTC date and time
+pub fn format_timestamp(timestamp: Option<i64>) -> String {
+    match timestamp {
+        Some(ts) => return format_time(ts, "%Y-%m-%d %H:%M:%S"),
+        None => return "N/A".to_string(),
+    }
+}
+
+// Format realtime timestamp (microseconds since epoch) as UTC time with time_format
+pub fn format_time(timestamp: i64, time_format: &str) -> String {
+    let nsec = (timestamp.rem_euclid(1000000) * 1000) as u32;
+    return UTC
+        .timestamp(timestamp.div_euclid(1000000), nsec)
+        .format(time_format)
+        .to_string();
+}
+
+// Format duration between two realtime timestamps as hours:minutes:seconds
+fn format_duration(first: Option<i64>, last: Option<i64>) -> String {
+    match (first, last) {
+        (Some(first), Some(last)) => {
+            let secs = (last - first) / 1000000;
+            return format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+        }
+        _ => return "N/A".to_string(),
+    }
+}
+
+// Get value of a field in a cursor string, e.g. "i" in "s=...;i=2a;b=..."
+fn cursor_field<'a>(cursor: &'a str, key: &str) -> Option<&'a str> {
+    for field in cursor.split(';') {
+        let mut kv = field.splitn(2, '=');
+        if kv.next() == Some(key) {
+            return kv.next();
+        }
+    }
+    return None;
+}
@@ -0,0 +1,184 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: stats.rs
+// File Description: Statistics summary of log entries
+// License: MIT
+//------------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::opt;
+use crate::parser::{self, FormattedLogEntry, LogEntry};
+
+//------------------------------------------------------------------------------
+// Statistics type
+//------------------------------------------------------------------------------
+
+// Entry counts of a set of log entries, e.g. one boot
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub entries: usize,
+    // Realtime timestamps of first and last entry, in microseconds
+    #[serde(skip)]
+    first_realtime: Option<i64>,
+    #[serde(skip)]
+    last_realtime: Option<i64>,
+    pub first: Option<String>,
+    pub last: Option<String>,
+    pub priorities: BTreeMap<String, usize>,
+    pub transports: BTreeMap<String, usize>,
+    pub units: BTreeMap<String, usize>,
+    // Entries per hour, "YYYY-MM-DD HH:00"
+    pub hours: BTreeMap<String, usize>,
+    #[serde(skip)]
+    identifiers: BTreeMap<String, usize>,
+    // Most frequent identifiers, set by finish
+    pub top_identifiers: Vec<IdentifierCount>,
+}
+
+// Entry count of one identifier
+#[derive(Debug, Serialize)]
+pub struct IdentifierCount {
+    pub identifier: String,
+    pub entries: usize,
+}
+
+// Statistics of one boot, for json output
+#[derive(Debug, Serialize)]
+pub struct BootStats {
+    pub boot: String,
+    pub host: String,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+
+// Complete statistics report, for json output
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    pub malformed_lines: usize,
+    pub overall: Stats,
+    pub boots: Vec<BootStats>,
+}
+
+impl Stats {
+    // Count entry
+    pub fn add(&mut self, log_entry: &LogEntry, entry: &FormattedLogEntry) {
+        self.entries += 1;
+
+        if self
+            .first_realtime
+            .is_none_or(|first| entry.realtime < first)
+        {
+            self.first_realtime = Some(entry.realtime);
+        }
+        if self.last_realtime.is_none_or(|last| entry.realtime > last) {
+            self.last_realtime = Some(entry.realtime);
+        }
+
+        let priority = opt::priority_name(entry.priority);
+        *self.priorities.entry(priority.to_string()).or_default() += 1;
+
+        let transport = log_entry._TRANSPORT.as_deref().unwrap_or("N/A");
+        *self.transports.entry(transport.to_string()).or_default() += 1;
+
+        *self.units.entry(entry.unit.clone()).or_default() += 1;
+
+        let hour = parser::format_time(entry.realtime, "%Y-%m-%d %H:00");
+        *self.hours.entry(hour).or_default() += 1;
+
+        // Identifier as for journalctl short output, falling back to unit name
+        let identifier = log_entry
+            .SYSLOG_IDENTIFIER
+            .clone()
+            .or_else(|| log_entry.field("_COMM"))
+            .unwrap_or_else(|| entry.unit.clone());
+        *self.identifiers.entry(identifier).or_default() += 1;
+    }
+
+    // Set formatted first and last timestamps and the top most frequent identifiers,
+    // when all entries are counted
+    pub fn finish(&mut self, top: usize) {
+        self.first = self
+            .first_realtime
+            .map(|first| parser::format_timestamp(Some(first)));
+        self.last = self
+            .last_realtime
+            .map(|last| parser::format_timestamp(Some(last)));
+        self.top_identifiers = by_count(&self.identifiers)
+            .into_iter()
+            .take(top)
+            .map(|(identifier, entries)| IdentifierCount {
+                identifier: identifier.clone(),
+                entries,
+            })
+            .collect();
+    }
+
+    // Print statistics as table
+    pub fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Entries : {}", self.entries)?;
+        writeln!(
+            out,
+            "Time : {} - {}",
+            self.first.as_deref().unwrap_or("N/A"),
+            self.last.as_deref().unwrap_or("N/A")
+        )?;
+
+        // Priorities in level order
+        let mut priorities: Vec<String> = Vec::new();
+        for name in opt::PRIORITY_NAMES.iter().chain(["N/A"].iter()) {
+            if let Some(count) = self.priorities.get(*name) {
+                priorities.push(format!("{}: {}", name, count));
+            }
+        }
+        writeln!(out, "Priority : {}", priorities.join(", "))?;
+
+        let transports: Vec<String> = by_count(&self.transports)
+            .iter()
+            .map(|(name, count)| format!("{}: {}", name, count))
+            .collect();
+        writeln!(out, "Transport : {}", transports.join(", "))?;
+
+        writeln!(out, "Units :")?;
+        print_counts(out, &by_count(&self.units))?;
+
+        writeln!(out, "Hours :")?;
+        let hours: Vec<(&String, usize)> = self.hours.iter().map(|(h, c)| (h, *c)).collect();
+        print_counts(out, &hours)?;
+
+        writeln!(out, "Top identifiers :")?;
+        let identifiers: Vec<(&String, usize)> = self
+            .top_identifiers
+            .iter()
+            .map(|i| (&i.identifier, i.entries))
+            .collect();
+        print_counts(out, &identifiers)?;
+        return Ok(());
+    }
+}
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+// Counts sorted by count, most frequent first
+fn by_count(counts: &BTreeMap<String, usize>) -> Vec<(&String, usize)> {
+    let mut sorted: Vec<(&String, usize)> = counts.iter().map(|(n, c)| (n, *c)).collect();
+    sorted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    return sorted;
+}
+
+fn print_counts(out: &mut dyn Write, counts: &[(&String, usize)]) -> io::Result<()> {
+    let width = counts
+        .iter()
+        .map(|(n, _)| n.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, count) in counts {
+        writeln!(out, "    {: <width$}  {}", name, count, width = width)?;
+    }
+    return Ok(());
+}
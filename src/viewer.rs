@@ -0,0 +1,637 @@
+//------------------------------------------------------------------------------
+// Project: jrnlvw
+// File name: viewer.rs
+// File Description: Interactive terminal viewer for log entries
+// License: MIT
+//------------------------------------------------------------------------------
+
+use chrono::prelude::*;
+use chrono::UTC;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use regex::Regex;
+
+use crate::opt;
+use crate::output;
+use crate::parser::{FormattedLogEntry, ParsedLogfile};
+
+// Width of the boot list
+const SIDEBAR_WIDTH: u16 = 34;
+
+const HELP: &str = "q quit  tab boots/entries  enter details  u unit  p priority  g grep  \
+t/T time  d/D date  c clear  / search  n/N next/prev  : jump to time";
+
+//------------------------------------------------------------------------------
+// Viewer types
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    Entries,
+    Boots,
+}
+
+// Filters editable in the viewer, same as the command line options
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Filter {
+    Unit,
+    Priority,
+    Grep,
+    TimeFrom,
+    TimeTo,
+    DateFrom,
+    DateTo,
+}
+
+const FILTERS: [Filter; 7] = [
+    Filter::Unit,
+    Filter::Priority,
+    Filter::Grep,
+    Filter::TimeFrom,
+    Filter::TimeTo,
+    Filter::DateFrom,
+    Filter::DateTo,
+];
+
+impl Filter {
+    fn name(self) -> &'static str {
+        match self {
+            Filter::Unit => return opt::UNIT_FILTER,
+            Filter::Priority => return opt::LOG_LEVEL,
+            Filter::Grep => return opt::GREP_FILTER,
+            Filter::TimeFrom => return opt::TIME_FROM,
+            Filter::TimeTo => return opt::TIME_TO,
+            Filter::DateFrom => return opt::DATE_FROM,
+            Filter::DateTo => return opt::DATE_TO,
+        }
+    }
+}
+
+// Line input prompt at the bottom of the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Prompt {
+    Filter(Filter),
+    Search,
+    Jump,
+}
+
+struct Viewer {
+    logfile: ParsedLogfile,
+    // Filtered entries, as displayed by the command line
+    entries: Vec<FormattedLogEntry>,
+    selected: usize,
+    offset: usize,
+    // Selected boot in boot list, 0 is all boots
+    boot_selected: usize,
+    focus: Focus,
+    details: bool,
+    prompt: Option<(Prompt, String)>,
+    search: Option<Regex>,
+    status: String,
+    // Set when the screen should be cleared before drawing
+    redraw: bool,
+    quit: bool,
+}
+
+//------------------------------------------------------------------------------
+// Viewer functions
+//------------------------------------------------------------------------------
+
+// Run the interactive viewer until the user quits
+pub fn run(logfile: ParsedLogfile) -> Result<(), failure::Error> {
+    let mut viewer = Viewer::new(logfile)?;
+    // Fails without a terminal, e.g. when run from a script
+    let mut terminal = ratatui::try_init()?;
+    let result = viewer.run(&mut terminal);
+    ratatui::restore();
+    return result;
+}
+
+impl Viewer {
+    fn new(logfile: ParsedLogfile) -> Result<Viewer, failure::Error> {
+        let entries = logfile.view_entries()?;
+
+        // Start with boot from command line selected, if only one
+        let boot_filter = logfile.options().boot_filter();
+        let boot_selected = match boot_filter.as_slice() {
+            [id] => logfile
+                .boot_ids()
+                .iter()
+                .position(|b| b == id)
+                .map_or(0, |p| p + 1),
+            _ => 0,
+        };
+
+        return Ok(Viewer {
+            logfile,
+            entries,
+            selected: 0,
+            offset: 0,
+            boot_selected,
+            focus: Focus::Entries,
+            details: false,
+            prompt: None,
+            search: None,
+            status: HELP.to_string(),
+            redraw: false,
+            quit: false,
+        });
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), failure::Error> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    break;
+                }
+
+                if self.prompt.is_some() {
+                    self.prompt_key(key.code);
+                } else {
+                    self.key(key.code, terminal.size()?.height as usize);
+                }
+
+                // Warnings printed while filtering may have overwritten the screen
+                if self.redraw {
+                    terminal.clear()?;
+                    self.redraw = false;
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Handle key in browse mode
+    fn key(&mut self, code: KeyCode, height: usize) {
+        let page = height.saturating_sub(4).max(1);
+        match code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if self.details => self.details = false,
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Entries => Focus::Boots,
+                    Focus::Boots => Focus::Entries,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-(page as i64)),
+            KeyCode::PageDown => self.move_selection(page as i64),
+            KeyCode::Home => self.move_selection(i64::MIN / 2),
+            KeyCode::End => self.move_selection(i64::MAX / 2),
+            KeyCode::Enter if self.focus == Focus::Boots => self.select_boot(),
+            KeyCode::Enter => self.details = !self.details,
+            KeyCode::Char('u') => self.start_prompt(Prompt::Filter(Filter::Unit)),
+            KeyCode::Char('p') => self.start_prompt(Prompt::Filter(Filter::Priority)),
+            KeyCode::Char('g') => self.start_prompt(Prompt::Filter(Filter::Grep)),
+            KeyCode::Char('t') => self.start_prompt(Prompt::Filter(Filter::TimeFrom)),
+            KeyCode::Char('T') => self.start_prompt(Prompt::Filter(Filter::TimeTo)),
+            KeyCode::Char('d') => self.start_prompt(Prompt::Filter(Filter::DateFrom)),
+            KeyCode::Char('D') => self.start_prompt(Prompt::Filter(Filter::DateTo)),
+            KeyCode::Char('c') => self.clear_filters(),
+            KeyCode::Char('/') => self.start_prompt(Prompt::Search),
+            KeyCode::Char(':') => self.start_prompt(Prompt::Jump),
+            KeyCode::Char('n') => self.search_next(true),
+            KeyCode::Char('N') => self.search_next(false),
+            _ => (),
+        }
+    }
+
+    // Handle key while prompt is active
+    fn prompt_key(&mut self, code: KeyCode) {
+        let (prompt, mut input) = match self.prompt.take() {
+            Some(p) => p,
+            None => return,
+        };
+        match code {
+            KeyCode::Esc => self.status = HELP.to_string(),
+            KeyCode::Enter => self.apply_prompt(prompt, input.trim()),
+            KeyCode::Backspace => {
+                input.pop();
+                self.prompt = Some((prompt, input));
+            }
+            KeyCode::Char(c) => {
+                input.push(c);
+                self.prompt = Some((prompt, input));
+            }
+            _ => self.prompt = Some((prompt, input)),
+        }
+    }
+
+    fn start_prompt(&mut self, prompt: Prompt) {
+        let input = match prompt {
+            Prompt::Filter(filter) => self.filter_text(filter),
+            Prompt::Search => self
+                .search
+                .as_ref()
+                .map_or(String::new(), |r| r.as_str().to_string()),
+            Prompt::Jump => String::new(),
+        };
+        self.prompt = Some((prompt, input));
+    }
+
+    fn apply_prompt(&mut self, prompt: Prompt, input: &str) {
+        match prompt {
+            Prompt::Filter(filter) => match self.set_filter(filter, input) {
+                Ok(()) => self.reload(),
+                Err(err) => self.status = format!("Invalid {}: {}", filter.name(), one_line(err)),
+            },
+            Prompt::Search if input.is_empty() => self.search = None,
+            Prompt::Search => match opt::grep_regex(input) {
+                Ok(regex) => {
+                    self.search = Some(regex);
+                    self.search_next(true);
+                }
+                Err(err) => self.status = format!("Invalid pattern: {}", one_line(err)),
+            },
+            Prompt::Jump => self.jump_to_time(input),
+        }
+    }
+
+    // Get current value of filter, in the format used on the command line
+    fn filter_text(&self, filter: Filter) -> String {
+        let options = self.logfile.options();
+        match filter {
+            Filter::Unit => {
+                // Leave out the .service variants added for unit names without suffix
+                let units = options.unit_filter();
+                let names: Vec<&str> = units
+                    .iter()
+                    .filter(|u| {
+                        !u.strip_suffix(".service")
+                            .is_some_and(|name| units.iter().any(|v| v == name))
+                    })
+                    .map(|u| u.as_str())
+                    .collect();
+                return names.join(",");
+            }
+            Filter::Priority => {
+                let (from, to) = (options.log_level_from(), options.log_level_to());
+                let name = |level: u32| opt::PRIORITY_NAMES[level as usize];
+                if from == 0 && to == 7 {
+                    return String::new();
+                } else if from == 0 {
+                    return name(to).to_string();
+                }
+                return format!("{}..{}", name(from), name(to));
+            }
+            Filter::Grep => return options.grep().map_or(String::new(), |r| r.to_string()),
+            Filter::TimeFrom => return format_time_of_day(options.start_time_filter()),
+            Filter::TimeTo => return format_time_of_day(options.stop_time_filter()),
+            Filter::DateFrom => return format_date(options.start_date_filter()),
+            Filter::DateTo => return format_date(options.stop_date_filter()),
+        }
+    }
+
+    // Set filter from input, in the format used on the command line. Units are separated
+    // by comma or space
+    fn set_filter(&mut self, filter: Filter, input: &str) -> Result<(), failure::Error> {
+        let options = self.logfile.options_mut();
+        match filter {
+            Filter::Unit => {
+                options.set_unit_filter(input.split([',', ' ']).filter(|u| !u.is_empty()));
+                return Ok(());
+            }
+            Filter::Priority => return options.set_log_level(input),
+            Filter::Grep => return options.set_grep(input),
+            Filter::TimeFrom => return options.set_time_from(input),
+            Filter::TimeTo => return options.set_time_to(input),
+            Filter::DateFrom => return options.set_date_from(input),
+            Filter::DateTo => return options.set_date_to(input),
+        }
+    }
+
+    // Clear all filters, including the boot filter
+    fn clear_filters(&mut self) {
+        for filter in FILTERS.iter() {
+            // Clearing a filter can't fail
+            let _ = self.set_filter(*filter, "");
+        }
+        self.logfile.options_mut().set_boot_filter(Vec::new());
+        self.boot_selected = 0;
+        self.reload();
+    }
+
+    // Filter entries with the current options, keeping the selected entry if still displayed
+    fn reload(&mut self) {
+        let current = self.entries.get(self.selected).map(|e| e.index);
+        match self.logfile.view_entries() {
+            Ok(entries) => self.entries = entries,
+            Err(err) => {
+                self.status = format!("Unable to filter log entries: {}", err);
+                return;
+            }
+        }
+        self.selected = current
+            .and_then(|index| self.entries.iter().position(|e| e.index == index))
+            .unwrap_or(0);
+        self.status = format!("{} entries", self.entries.len());
+        self.redraw = true;
+    }
+
+    // Move selection in the focused list
+    fn move_selection(&mut self, delta: i64) {
+        let (selected, len) = match self.focus {
+            Focus::Entries => (&mut self.selected, self.entries.len()),
+            Focus::Boots => (&mut self.boot_selected, self.logfile.boot_ids().len() + 1),
+        };
+        let max = len.saturating_sub(1) as i64;
+        *selected = (*selected as i64 + delta).clamp(0, max) as usize;
+    }
+
+    // Filter entries on the boot selected in the boot list
+    fn select_boot(&mut self) {
+        let boot_filter = match self.boot_selected {
+            0 => Vec::new(),
+            n => vec![self.logfile.boot_ids()[n - 1].clone()],
+        };
+        self.logfile.options_mut().set_boot_filter(boot_filter);
+        self.reload();
+        self.focus = Focus::Entries;
+    }
+
+    // Select next (or previous) entry with message matching the search pattern
+    fn search_next(&mut self, forward: bool) {
+        let regex = match self.search.as_ref() {
+            Some(r) => r,
+            None => {
+                self.status = "No search pattern, use / to search".to_string();
+                return;
+            }
+        };
+        let matches = |e: &FormattedLogEntry| regex.is_match(&e.message);
+        let found = if forward {
+            self.entries
+                .iter()
+                .enumerate()
+                .skip(self.selected + 1)
+                .find(|(_, e)| matches(e))
+        } else {
+            self.entries
+                .iter()
+                .enumerate()
+                .take(self.selected)
+                .rev()
+                .find(|(_, e)| matches(e))
+        };
+        match found {
+            Some((position, _)) => {
+                self.selected = position;
+                self.focus = Focus::Entries;
+                self.status = format!("/{}", regex);
+            }
+            None => self.status = format!("Pattern not found: {}", regex),
+        }
+    }
+
+    // Select first entry at or after time, "[YYYY:MM:DD] HH:MM:SS". The date of the
+    // selected entry is used if no date is given
+    fn jump_to_time(&mut self, input: &str) {
+        let (date, time) = match input.split_once(' ') {
+            Some((date, time)) => (date.trim(), time.trim()),
+            None => ("", input),
+        };
+        let time = match opt::parse_time_of_day(time) {
+            Ok(t) => t,
+            Err(err) => {
+                self.status = format!("Invalid time: {}", err);
+                return;
+            }
+        };
+        let date = if date.is_empty() {
+            let current = self.entries.get(self.selected).map_or(0, |e| e.realtime);
+            let seconds = current / 1000000;
+            seconds - seconds.rem_euclid(86400)
+        } else {
+            match opt::parse_date(date) {
+                Ok(d) => d,
+                Err(err) => {
+                    self.status = format!("Invalid date: {}", err);
+                    return;
+                }
+            }
+        };
+
+        let target = (date + time) * 1000000;
+        match self.entries.iter().position(|e| e.realtime >= target) {
+            Some(position) => {
+                self.selected = position;
+                self.focus = Focus::Entries;
+                self.status = format!("Jumped to {}", self.entries[position].timestamp);
+            }
+            None => self.status = format!("No entries after {}", input),
+        }
+    }
+
+    //--------------------------------------------------------------------------
+    // Drawing
+    //--------------------------------------------------------------------------
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, filters, footer] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [sidebar, content] =
+            Layout::horizontal([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(10)])
+                .areas(main);
+
+        self.draw_boots(frame, sidebar);
+        if self.details {
+            let [list, details] =
+                Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(content);
+            self.draw_entries(frame, list);
+            self.draw_details(frame, details);
+        } else {
+            self.draw_entries(frame, content);
+        }
+
+        // Active filters
+        let active: Vec<String> = FILTERS
+            .iter()
+            .map(|f| (f.name(), self.filter_text(*f)))
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        let filter_line = if active.is_empty() {
+            "Filters: none".to_string()
+        } else {
+            format!("Filters: {}", active.join("  "))
+        };
+        frame.render_widget(
+            Paragraph::new(filter_line).style(Style::default().add_modifier(Modifier::REVERSED)),
+            filters,
+        );
+
+        // Prompt or status line
+        let footer_line = match self.prompt.as_ref() {
+            Some((prompt, input)) => {
+                let label = match prompt {
+                    Prompt::Filter(filter) => filter.name(),
+                    Prompt::Search => "search",
+                    Prompt::Jump => "jump to [YYYY:MM:DD] HH:MM:SS",
+                };
+                let line = format!("{}: {}", label, input);
+                let cursor_x = footer.x + line.chars().count() as u16;
+                frame.set_cursor_position((cursor_x.min(footer.right()), footer.y));
+                line
+            }
+            None => self.status.clone(),
+        };
+        frame.render_widget(Paragraph::new(footer_line), footer);
+    }
+
+    fn draw_boots(&self, frame: &mut Frame, area: Rect) {
+        let mut items = vec![ListItem::new("All boots")];
+        for (index, id) in self.logfile.boot_ids().iter().enumerate() {
+            // Boot index offset from the last boot, as in the boot list
+            let offset = index as i64 + 1 - self.logfile.boot_ids().len() as i64;
+            let description = self.logfile.boot_description(id);
+            items.push(ListItem::new(format!("{:>3} {}", offset, description)));
+        }
+
+        let mut state = ListState::default().with_selected(Some(self.boot_selected));
+        let list = List::new(items)
+            .block(focus_block("Boots", self.focus == Focus::Boots))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_entries(&mut self, frame: &mut Frame, area: Rect) {
+        // Only visible entries are rendered, keep the selected entry in view
+        let height = area.height.saturating_sub(2) as usize;
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if height > 0 && self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let end = (self.offset + height).min(self.entries.len());
+        let items: Vec<ListItem> = self.entries[self.offset..end]
+            .iter()
+            .map(|e| {
+                let message = e.message.lines().next().unwrap_or("");
+                let line = Line::from(vec![
+                    Span::raw(format!("{: <6} {}  ", e.sequence_number, e.timestamp)),
+                    Span::styled(format!("{: <3}", e.loglevel), priority_style(e.priority)),
+                    Span::styled(
+                        format!("{: <20} ", e.unit_name),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(message.to_string(), priority_style(e.priority)),
+                ]);
+                ListItem::new(line)
+            })
+            .collect();
+
+        let title = format!(
+            "'{}' {}/{} entries",
+            self.logfile.filename(),
+            if self.entries.is_empty() {
+                0
+            } else {
+                self.selected + 1
+            },
+            self.entries.len()
+        );
+        let selected = if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.selected - self.offset)
+        };
+        let mut state = ListState::default().with_selected(selected);
+        let list = List::new(items)
+            .block(focus_block(&title, self.focus == Focus::Entries))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    // Draw all fields of selected entry, as verbose output
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let mut text = Vec::new();
+        if let Some(entry) = self.entries.get(self.selected) {
+            let log_entry = self.logfile.log_entry(entry.index);
+            if let Err(err) = output::print_verbose(&mut text, log_entry) {
+                text = format!("Unable to display entry: {}", err).into_bytes();
+            }
+        }
+        let lines: Vec<Line> = String::from_utf8_lossy(&text)
+            .lines()
+            .map(|l| Line::from(l.to_string()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Details")),
+            area,
+        );
+    }
+}
+
+//------------------------------------------------------------------------------
+// Helper functions
+//------------------------------------------------------------------------------
+
+// Bordered block, with highlighted border if focused
+fn focus_block(title: &str, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Blue)
+    } else {
+        Style::default()
+    };
+    return Block::bordered()
+        .title(title.to_string())
+        .border_style(style);
+}
+
+// Style for priority, same colors as the command line output
+fn priority_style(priority: Option<u32>) -> Style {
+    match priority {
+        Some(0..=3) => return Style::default().fg(Color::Red),
+        Some(4) => return Style::default().fg(Color::Yellow),
+        Some(5) => return Style::default().add_modifier(Modifier::BOLD),
+        Some(7) => return Style::default().add_modifier(Modifier::DIM),
+        _ => return Style::default(),
+    }
+}
+
+// Error message as a single line, regex errors span multiple lines
+fn one_line(err: failure::Error) -> String {
+    return err
+        .to_string()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+}
+
+// Format seconds from midnight as HH:MM:SS, empty if not set
+fn format_time_of_day(seconds: i64) -> String {
+    if seconds == 0 {
+        return String::new();
+    }
+    return format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    );
+}
+
+// Format UTC timestamp as YYYY:MM:DD, empty if not set
+fn format_date(timestamp: i64) -> String {
+    if timestamp == 0 {
+        return String::new();
+    }
+    return UTC.timestamp(timestamp, 0).format("%Y:%m:%d").to_string();
+}